@@ -262,6 +262,10 @@ except ImportError:
         def __del__(self):
             self.sock.close()
 
+        def begin_frame(self):
+            """Frames aren't tracked or throttled here, so every frame may be sent."""
+            return True
+
         def create_dmx_packet(self, universe, data):
             """
             Manually construct an ArtNet DMX packet.
@@ -307,6 +311,12 @@ except ImportError:
             z_indices=None,
         ):
             """Sends the raster data via ArtNet using NumPy for high performance."""
+            if channels_per_universe <= 0 or channels_per_universe % 3 != 0:
+                # A pixel's channels would otherwise be split across two universes
+                raise ValueError(
+                    f"channels_per_universe must be a positive multiple of 3 "
+                    f"(got {channels_per_universe})"
+                )
             if z_indices is None:
                 z_indices = range(raster.length)
 
@@ -6,9 +6,12 @@ of the control port system, which manages multiple control ports and
 provides web monitoring capabilities.
 """
 
+import asyncio
+import json
 from typing import Any, Callable, Dict, List, Optional
 
 from src.control_port.control_port_rs import ControlPortManager as ControlPortManagerRs
+from src.control_port.control_port_rs import init_logging as init_logging_rs
 
 
 class ControlPortManager:
@@ -90,6 +93,58 @@ class ControlPortManager:
         """
         return self._control_ports.copy()
 
+    def add_control_port(self, dip: str, ip: str, port: int, **options: Any) -> "ControlPort":
+        """
+        Add and start a control port for a controller discovered at runtime.
+
+        Args:
+            dip: DIP address of the controller
+            ip: IP address of the controller
+            port: TCP port of the controller
+            **options: Other controller config fields (e.g. led_encoding="raw")
+
+        Returns:
+            The new ControlPort
+
+        Raises:
+            RuntimeError: If a control port for this DIP already exists
+        """
+        config_json = json.dumps({"ip": ip, "port": port, **options})
+        control_port = ControlPort(self._rust_manager.add_control_port(dip, config_json))
+        self._control_ports[dip] = control_port
+        return control_port
+
+    def remove_control_port(self, dip: str) -> bool:
+        """
+        Disconnect and remove a control port.
+
+        Args:
+            dip: DIP address of the control port
+
+        Returns:
+            True if the control port existed and was removed
+        """
+        self._control_ports.pop(dip, None)
+        return self._rust_manager.remove_control_port(dip)
+
+    def list_dips(self) -> List[str]:
+        """
+        Get the DIP addresses of all managed control ports.
+
+        Returns:
+            Sorted list of DIP addresses
+        """
+        return self._rust_manager.list_dips()
+
+    def list_control_ports(self) -> List[Dict[str, Any]]:
+        """
+        Get the address of every managed control port.
+
+        Returns:
+            List of dictionaries with "dip", "ip" and "port" keys
+        """
+        return self._rust_manager.list_control_ports()
+
     def get_stats(self) -> List[Dict[str, Any]]:
         """
         Get statistics for all control ports.
@@ -107,10 +162,7 @@ class ControlPortManager:
 
     def _get_configured_dips(self) -> List[str]:
         """Get list of configured DIP addresses from the config."""
-        # This is a simplified approach - in practice, you might want to
-        # parse the config file directly or add a method to the Rust side
-        stats = self._rust_manager.get_all_stats()
-        return [stat["dip"] for stat in stats]
+        return self._rust_manager.list_dips()
 
 
 class ControlPort:
@@ -139,6 +191,15 @@ class ControlPort:
         """Clear the LCD display (compatibility method)."""
         self._rust_port.clear_display()
 
+    def clear_line(self, y: int) -> None:
+        """
+        Blank a single LCD line, leaving the rest of the display untouched.
+
+        Args:
+            y: Y coordinate (row) to clear
+        """
+        self._rust_port.clear_line(y)
+
     def write_display(self, x: int, y: int, text: str) -> None:
         """
         Write text to the LCD display at specified coordinates.
@@ -161,6 +222,20 @@ class ControlPort:
         """
         self._rust_port.write_display(x, y, text)
 
+    def write_display_wrap(self, x: int, y: int, text: str) -> None:
+        """
+        Write text to the LCD display, wrapping onto the following lines.
+
+        Text is word-wrapped where possible (continuation lines start at column 0)
+        and anything past the bottom of the display is dropped.
+
+        Args:
+            x: X coordinate (column) of the first character
+            y: Y coordinate (row) of the first line
+            text: Text to display; newlines force a line break
+        """
+        self._rust_port.write_display_wrap(x, y, text)
+
     async def commit_display(self) -> None:
         """Commit pending display changes to the controller."""
         # The Rust commit_display() method returns PyResult<()> which is Ok(()) on success
@@ -184,6 +259,15 @@ class ControlPort:
         """Commit display changes (alias for commit_display)."""
         return await self.commit_display()
 
+    def get_display_snapshot(self) -> List[str]:
+        """
+        Get the committed LCD contents.
+
+        Returns:
+            One string per display row, padded with spaces to the display width
+        """
+        return self._rust_port.get_display_snapshot()
+
     def set_leds(self, rgb_values: List[tuple]) -> None:
         """
         Set LED colors.
@@ -193,6 +277,19 @@ class ControlPort:
         """
         self._rust_port.set_leds(rgb_values)
 
+    def fade_leds(self, target: List[tuple], duration_ms: int) -> None:
+        """
+        Fade LED colors from their current state to a target.
+
+        Intermediate frames are sent from the Rust side; calling set_leds or
+        fade_leds again cancels a fade in progress.
+
+        Args:
+            target: List of (r, g, b) tuples for each LED
+            duration_ms: Fade duration in milliseconds
+        """
+        self._rust_port.fade_leds(target, duration_ms)
+
     def set_backlights(self, states: List[bool]) -> None:
         """
         Set backlight states.
@@ -215,6 +312,46 @@ class ControlPort:
         receiver = self._rust_port.register_button_callback(callback)
         receiver.start_listening()
 
+    def button_events(self) -> "ButtonEventStream":
+        """
+        Get an async iterator of button events.
+
+        Each subscriber receives every button state from the time it was created,
+        so this can be used alongside register_button_callback:
+
+            async for buttons in control_port.button_events():
+                ...
+
+        Returns:
+            ButtonEventStream yielding lists of boolean button states
+        """
+        return ButtonEventStream(self._rust_port.button_events())
+
+    def register_analog_callback(self, callback: Callable[[List[float]], None]) -> None:
+        """
+        Register a callback function for analog inputs (e.g. pots).
+
+        Args:
+            callback: Function to call when a button message carries analog values.
+                     Takes a list of floats in the order the controller reports them.
+        """
+        receiver = self._rust_port.register_analog_callback(callback)
+        receiver.start_listening()
+
+    def register_message_callback(self, callback: Callable[[Dict[str, Any]], None]) -> None:
+        """
+        Register a callback function for unrecognized controller messages.
+
+        Args:
+            callback: Function to call with the decoded JSON object of any message
+                     that isn't a heartbeat, controller or button message
+                     (e.g. custom firmware messages like {"type": "encoder", "delta": 1}).
+        """
+        receiver = self._rust_port.register_message_callback(
+            lambda json_str: callback(json.loads(json_str))
+        )
+        receiver.start_listening()
+
     @property
     def dip(self) -> str:
         """Get the DIP address of this control port."""
@@ -238,6 +375,36 @@ class ControlPort:
         return 0
 
 
+class ButtonEventStream:
+    """
+    Async iterator over button events from a single control port.
+
+    Waiting happens on an executor thread (the GIL is released while blocked),
+    so iterating does not stall the event loop. Iteration ends when the control
+    port shuts down.
+    """
+
+    # How long each executor call waits before yielding back to the event loop
+    POLL_TIMEOUT_MS = 100
+
+    def __init__(self, rust_stream):
+        self._rust_stream = rust_stream
+
+    def __aiter__(self) -> "ButtonEventStream":
+        return self
+
+    async def __anext__(self) -> List[bool]:
+        loop = asyncio.get_running_loop()
+        while True:
+            buttons = await loop.run_in_executor(
+                None, self._rust_stream.next_event, self.POLL_TIMEOUT_MS
+            )
+            if buttons is not None:
+                return buttons
+            if self._rust_stream.is_closed():
+                raise StopAsyncIteration
+
+
 def create_control_port_from_config(
     config_path: str,
     web_monitor_port: int = 8080,
@@ -277,3 +444,19 @@ def create_control_port_manager(
         Initialized ControlPortManager instance
     """
     return create_control_port_from_config(config_path, web_monitor_port)
+
+
+def init_logging(level: str = "info") -> bool:
+    """
+    Forward the Rust control port diagnostics to Python's logging module.
+
+    Events (connection attempts, read and parse errors, ...) are logged under loggers named
+    after their Rust module, e.g. "control_port_rs.control_port".
+
+    Args:
+        level: Lowest level forwarded: trace, debug, info, warn, error or off (default: "info")
+
+    Returns:
+        False if logging was already set up by an earlier call
+    """
+    return init_logging_rs(level)
@@ -256,6 +256,7 @@ def apply_mapping_tester(raster, debug_command, artnet_manager):
 
     orientation = mapping_data.get("orientation", "xy")
     layer = mapping_data.get("layer", 0)
+    all_layers = mapping_data.get("all_layers", False)
     color_hex = mapping_data.get("color", "#FF0000")
     target = mapping_data.get("target", "world")
 
@@ -267,7 +268,7 @@ def apply_mapping_tester(raster, debug_command, artnet_manager):
 
     if target == "world":
         # Apply to world raster
-        apply_mapping_tester_to_raster(raster, orientation, layer, color)
+        apply_mapping_tester_to_raster(raster, orientation, layer, color, all_layers)
     elif target.startswith("cube_"):
         # Apply to specific cube raster
         cube_index = int(target.split("_")[1])
@@ -285,7 +286,7 @@ def apply_mapping_tester(raster, debug_command, artnet_manager):
             if cube_raster:
                 # For per-cube debug mode, apply debug commands directly to the cube raster
                 # without any orientation transformation - this shows the cube's raw coordinate system
-                apply_mapping_tester_to_raster(cube_raster, orientation, layer, color)
+                apply_mapping_tester_to_raster(cube_raster, orientation, layer, color, all_layers)
 
                 cubes_with_debug_commands.add(cube_position)
                 logger.debug(
@@ -301,27 +302,33 @@ def apply_mapping_tester(raster, debug_command, artnet_manager):
     return cubes_with_debug_commands
 
 
-def apply_mapping_tester_to_raster(raster, orientation, layer, color):
+def apply_mapping_tester_to_raster(raster, orientation, layer, color, all_layers=False):
     """Apply mapping tester to a specific raster (world or cube)."""
     # Clear the raster first
     raster.clear()
 
-    # Light up the specified plane
+    # Light up the specified plane, or every plane in this orientation
     if orientation == "xy":
         # XY plane at specific Z layer
-        for x in range(raster.width):
-            for y in range(raster.height):
-                raster.set_pix(x, y, layer, color)
+        layers = range(raster.length) if all_layers else [layer]
+        for z in layers:
+            for x in range(raster.width):
+                for y in range(raster.height):
+                    raster.set_pix(x, y, z, color)
     elif orientation == "xz":
         # XZ plane at specific Y layer
-        for x in range(raster.width):
-            for z in range(raster.length):
-                raster.set_pix(x, layer, z, color)
+        layers = range(raster.height) if all_layers else [layer]
+        for y in layers:
+            for x in range(raster.width):
+                for z in range(raster.length):
+                    raster.set_pix(x, y, z, color)
     elif orientation == "yz":
         # YZ plane at specific X layer
-        for y in range(raster.height):
-            for z in range(raster.length):
-                raster.set_pix(layer, y, z, color)
+        layers = range(raster.width) if all_layers else [layer]
+        for x in layers:
+            for y in range(raster.height):
+                for z in range(raster.length):
+                    raster.set_pix(x, y, z, color)
 
 
 def apply_power_draw_tester(raster, debug_command, current_time):
@@ -491,6 +498,10 @@ def main():
         last_warning_time = defaultdict(float)  # controller_ip -> last_warning_time
         WARNING_INTERVAL = 10.0  # Only show warnings every 10 seconds per controller
 
+        # Cached debug command, refetched only when the monitor's version changes
+        debug_command = None
+        debug_command_version = None
+
         # Main rendering and transmission loop
         logger.info("🎬 Starting main loop...")
         start_time = time.time()
@@ -509,7 +520,10 @@ def main():
             # Check if we're in debug mode and paused
             if sender_monitor and sender_monitor.is_debug_mode() and sender_monitor.is_paused():
                 # In debug mode and paused - don't update scene, just apply debug commands
-                debug_command = sender_monitor.get_debug_command()
+                version = sender_monitor.get_debug_command_version()
+                if version != debug_command_version:
+                    debug_command = sender_monitor.get_debug_command()
+                    debug_command_version = version
                 if debug_command:
                     cubes_with_debug_commands = apply_debug_commands(
                         world_raster, debug_command, current_time, artnet_manager
@@ -569,6 +583,9 @@ def main():
             t_slice_done = time.monotonic()
 
             # C. SEND: Iterate through all jobs and send the specified Z-layers.
+            # Each controller's jobs make up one frame, for its fps cap and delta resends
+            for controller in artnet_manager.controllers_cache.values():
+                controller.begin_frame()
             conversion_cache = {}
             for job in artnet_manager.send_jobs:
                 # Get the original raster with its NumPy data
@@ -610,7 +627,14 @@ def main():
 
                     # Report success to monitor if available
                     if sender_monitor:
-                        sender_monitor.report_controller_success(controller_ip, controller_port)
+                        # DMX payload only, excluding Art-Net packet headers
+                        payload_bytes = (
+                            len(job["z_indices"]) * cube_raster.width * cube_raster.height * 3
+                        )
+                        sender_monitor.report_controller_success(
+                            controller_ip, controller_port, payload_bytes
+                        )
+                        sender_monitor.report_bytes(payload_bytes)
 
                 except (OSError, ConnectionError, TimeoutError) as e:
                     # Track failures and log warnings periodically
@@ -76,15 +76,29 @@ class SenderMonitorWrapper:
         self._is_paused = False
         self._debug_command = None
 
-    def register_controller(self, ip: str, port: int) -> None:
-        """Register a controller for monitoring."""
+    def register_controller(self, ip: str, port: int, group: str | None = None) -> bool:
+        """Register a controller for monitoring, optionally tagged with a group/zone.
+
+        Returns False if it was already registered; its existing stats are kept.
+        """
+        if self.monitor:
+            return self.monitor.register_controller(ip, port, group)
+        return True
+
+    def set_controller_group(self, ip: str, port: int, group: str | None) -> None:
+        """Set or clear the group/zone label of a registered controller."""
+        if self.monitor:
+            self.monitor.set_controller_group(ip, port, group)
+
+    def set_activity_ttl(self, ttl_seconds: int | None) -> None:
+        """Expire controllers with no reported success/failure within ttl_seconds (None disables)."""
         if self.monitor:
-            self.monitor.register_controller(ip, port)
+            self.monitor.set_activity_ttl(ttl_seconds)
 
-    def report_controller_success(self, ip: str, port: int) -> None:
-        """Report successful transmission to a controller."""
+    def report_controller_success(self, ip: str, port: int, num_bytes: int | None = None) -> None:
+        """Report successful transmission to a controller, optionally with the bytes sent."""
         if self.monitor:
-            self.monitor.report_controller_success(ip, port)
+            self.monitor.report_controller_success(ip, port, num_bytes)
 
     def report_controller_failure(self, ip: str, port: int, error: str) -> None:
         """Report failed transmission to a controller."""
@@ -96,6 +110,16 @@ class SenderMonitorWrapper:
         if self.monitor:
             self.monitor.report_frame()
 
+    def reset_frame_stats(self) -> None:
+        """Restart the FPS average, e.g. after loading a new show."""
+        if self.monitor:
+            self.monitor.reset_frame_stats()
+
+    def report_bytes(self, num_bytes: int) -> None:
+        """Report bytes sent over Art-Net, for aggregate bandwidth stats."""
+        if self.monitor:
+            self.monitor.report_bytes(num_bytes)
+
     def set_debug_mode(self, enabled: bool) -> None:
         """Enable or disable debug mode."""
         self._debug_mode = enabled
@@ -120,6 +144,18 @@ class SenderMonitorWrapper:
             return self.monitor.is_paused()
         return self._is_paused
 
+    def wait_for_pause_change(self, current: bool, timeout_ms: int | None = None) -> bool:
+        """Block until the pause state differs from current (or timeout) and return it."""
+        if self.monitor:
+            return self.monitor.wait_for_pause_change(current, timeout_ms)
+        return self._is_paused
+
+    def get_debug_command_version(self) -> int:
+        """Get a counter that increases whenever the debug command changes."""
+        if self.monitor:
+            return self.monitor.get_debug_command_version()
+        return 0
+
     def get_debug_command(self):
         """Get the current debug command if any."""
         if self.monitor:
@@ -136,6 +172,12 @@ class SenderMonitorWrapper:
         if self.monitor:
             self.monitor.set_cube_list(cubes)
 
+    def get_web_monitor_port(self) -> int | None:
+        """Get the port the web interface is bound to, or None if it isn't running."""
+        if self.monitor:
+            return self.monitor.get_web_monitor_port()
+        return None
+
     def shutdown(self) -> None:
         """Shutdown the monitor."""
         if self.monitor:
@@ -1,11 +1,234 @@
 use pyo3::prelude::*;
-use pyo3::types::PyList;
-use std::net::UdpSocket;
+use pyo3::types::{PyBytes, PyList};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 fn saturate_u8(value: f32) -> u8 {
     value.max(0.0).min(255.0) as u8
 }
 
+// Lookup table mapping each channel value through a gamma curve
+fn gamma_lut(gamma: f32) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, out) in lut.iter_mut().enumerate() {
+        *out = saturate_u8(((i as f32 / 255.0).powf(gamma) * 255.0).round());
+    }
+    lut
+}
+
+// Lookup table scaling each channel value by a white-balance factor
+fn balance_lut(scale: f32) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, out) in lut.iter_mut().enumerate() {
+        *out = saturate_u8((i as f32 * scale).round());
+    }
+    lut
+}
+
+// 64-bit FNV-1a; deterministic across runs and platforms
+fn fnv1a_64(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+// Hue step between adjacent universes in the rainbow test pattern
+const TEST_PATTERN_RAINBOW_HUE_STEP: usize = 40;
+const TEST_PATTERN_NAMES: &str = "red, green, blue, white, rainbow or moving_pixel";
+
+// Packed (z, y, x, rgb) bytes for a named bring-up pattern. Patterns: red, green, blue, white,
+// rainbow (one hue per universe) and moving_pixel (a single white voxel at index `step`).
+fn test_pattern_bytes(
+    pattern: &str,
+    width: usize,
+    height: usize,
+    length: usize,
+    channels_per_universe: usize,
+    universes_per_layer: u16,
+    step: usize,
+) -> Result<Vec<u8>, String> {
+    let layer_voxels = width * height;
+    let voxels = layer_voxels * length;
+    let solid = |rgb: [u8; 3]| rgb.repeat(voxels);
+    let bytes = match pattern {
+        "red" => solid([255, 0, 0]),
+        "green" => solid([0, 255, 0]),
+        "blue" => solid([0, 0, 255]),
+        "white" => solid([255, 255, 255]),
+        "rainbow" => {
+            let mut bytes = Vec::with_capacity(voxels * 3);
+            for z in 0..length {
+                for i in 0..layer_voxels {
+                    let universe = z * universes_per_layer as usize + i * 3 / channels_per_universe;
+                    let hue = (universe * TEST_PATTERN_RAINBOW_HUE_STEP % 256) as u8;
+                    let (r, g, b) = hsv_to_rgb(hue, 255, 255);
+                    bytes.extend_from_slice(&[r, g, b]);
+                }
+            }
+            bytes
+        }
+        "moving_pixel" => {
+            let mut bytes = vec![0u8; voxels * 3];
+            if voxels > 0 {
+                let idx = step % voxels * 3;
+                bytes[idx..idx + 3].copy_from_slice(&[255, 255, 255]);
+            }
+            bytes
+        }
+        _ => {
+            return Err(format!(
+                "Unknown test pattern '{}' (expected {})",
+                pattern, TEST_PATTERN_NAMES
+            ))
+        }
+    };
+    Ok(bytes)
+}
+
+// Hue is scaled so that 0..256 spans the full color wheel, matching the Python RGB.from_hsv
+fn hsv_to_rgb(hue: u8, saturation: u8, value: u8) -> (u8, u8, u8) {
+    let h = hue as f32 / (256.0 / 6.0);
+    let s = saturation as f32 / 255.0;
+    let v = value as f32 / 255.0;
+
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = if h < 1.0 {
+        (c, x, 0.0)
+    } else if h < 2.0 {
+        (x, c, 0.0)
+    } else if h < 3.0 {
+        (0.0, c, x)
+    } else if h < 4.0 {
+        (0.0, x, c)
+    } else if h < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+
+    (
+        saturate_u8((r + m) * 255.0),
+        saturate_u8((g + m) * 255.0),
+        saturate_u8((b + m) * 255.0),
+    )
+}
+
+fn rgb_to_hsv(red: u8, green: u8, blue: u8) -> (u8, u8, u8) {
+    let r = red as f32;
+    let g = green as f32;
+    let b = blue as f32;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0, 0, saturate_u8(max));
+    }
+
+    let h = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (
+        saturate_u8(h * (256.0 / 6.0)),
+        saturate_u8(delta / max * 255.0),
+        saturate_u8(max),
+    )
+}
+
+// Art-Net 4 protocol revision, reported in every packet's ProtVer field
+const DEFAULT_ARTNET_PROTOCOL_VERSION: u16 = 14;
+
+// Default cap on width * height * length, to catch typo'd dimensions before allocating
+const DEFAULT_MAX_RASTER_VOXELS: usize = 16 * 1024 * 1024;
+
+// Default for ArtNetController.set_max_brightness, which clamps every raster's brightness when
+// sending; content is authored for unity
+const DEFAULT_MAX_BRIGHTNESS: f32 = 1.0;
+
+// DMX channels per voxel (RGB); universes must hold whole pixels
+const CHANNELS_PER_PIXEL: usize = 3;
+
+// Art-Net Port-Addresses are 15 bits
+const MAX_ARTNET_UNIVERSE: u64 = 0x7FFF;
+
+// The first of `layers` output layers whose universes run past MAX_ARTNET_UNIVERSE when laid out
+// like send_dmx, with the last universe it would need
+fn universe_overflow(
+    base_universe: u16,
+    layer_universes: usize,
+    universes_per_layer: u16,
+    channel_span: usize,
+    layers: usize,
+) -> Option<(usize, u64)> {
+    (0..layers).find_map(|out_z| {
+        let last = base_universe as u64
+            + (out_z / channel_span) as u64 * universes_per_layer as u64
+            + layer_universes.saturating_sub(1) as u64;
+        (last > MAX_ARTNET_UNIVERSE).then_some((out_z, last))
+    })
+}
+
+fn check_brightness(name: &str, value: f32) -> PyResult<()> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "{} must be a non-negative number, got {}",
+            name, value
+        )));
+    }
+    Ok(())
+}
+
+// Treat limited broadcast and any x.x.x.255 address as broadcast
+fn is_broadcast_ip(ip: &str) -> bool {
+    ip == "255.255.255.255" || ip.ends_with(".255")
+}
+
+// Bind the sending socket to a local interface ("ip" or "ip:port"), defaulting to 0.0.0.0:0.
+// SO_REUSEADDR lets a monitor share the address.
+fn bind_artnet_socket(bind_address: Option<&str>) -> std::io::Result<UdpSocket> {
+    let addr = match bind_address {
+        None => SocketAddr::from(([0, 0, 0, 0], 0)),
+        Some(address) => address
+            .parse::<SocketAddr>()
+            .or_else(|_| address.parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 0)))
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("Invalid bind address: {}", address),
+                )
+            })?,
+    };
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    Ok(socket.into())
+}
+
+// The deadline after `deadline` for a frame that woke at `tick`. A frame that ran more than a
+// period late restarts the schedule from now instead of bursting to catch up.
+fn next_pace_deadline(deadline: Instant, tick: Instant, period: Duration) -> Instant {
+    let next = deadline + period;
+    if next > tick {
+        next
+    } else {
+        tick + period
+    }
+}
+
 #[pymodule]
 mod artnet_rs {
     use super::*;
@@ -27,33 +250,8 @@ mod artnet_rs {
 
         #[staticmethod]
         fn from_hsv(hsv: &HSV) -> Self {
-            let h = hsv.hue as f32 / (256.0 / 6.0);
-            let s = hsv.saturation as f32 / 255.0;
-            let v = hsv.value as f32 / 255.0;
-
-            let c = v * s;
-            let x = c * (1.0 - (h % 2.0 - 1.0).abs());
-            let m = v - c;
-
-            let (r, g, b) = if h < 1.0 {
-                (c, x, 0.0)
-            } else if h < 2.0 {
-                (x, c, 0.0)
-            } else if h < 3.0 {
-                (0.0, c, x)
-            } else if h < 4.0 {
-                (0.0, x, c)
-            } else if h < 5.0 {
-                (x, 0.0, c)
-            } else {
-                (c, 0.0, x)
-            };
-
-            RGB {
-                red: saturate_u8((r + m) * 255.0),
-                green: saturate_u8((g + m) * 255.0),
-                blue: saturate_u8((b + m) * 255.0),
-            }
+            let (red, green, blue) = hsv_to_rgb(hsv.hue, hsv.saturation, hsv.value);
+            RGB { red, green, blue }
         }
     }
 
@@ -77,6 +275,37 @@ mod artnet_rs {
         }
     }
 
+    // Convert a batch of hues at fixed saturation/value into packed RGB bytes
+    #[pyfunction]
+    fn hsv_array_to_rgb_bytes(hues: Vec<u8>, saturation: u8, value: u8) -> Vec<u8> {
+        let mut rgb_bytes = Vec::with_capacity(hues.len() * 3);
+        for hue in hues {
+            let (r, g, b) = hsv_to_rgb(hue, saturation, value);
+            rgb_bytes.extend_from_slice(&[r, g, b]);
+        }
+        rgb_bytes
+    }
+
+    // Convert packed RGB bytes into packed HSV bytes
+    #[pyfunction]
+    fn rgb_bytes_to_hsv_bytes(rgb_bytes: Vec<u8>) -> PyResult<Vec<u8>> {
+        if !rgb_bytes.len().is_multiple_of(3) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "RGB data length {} is not a multiple of 3",
+                rgb_bytes.len()
+            )));
+        }
+        let mut hsv_bytes = Vec::with_capacity(rgb_bytes.len());
+        for rgb in rgb_bytes.chunks_exact(3) {
+            let (h, s, v) = rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+            hsv_bytes.extend_from_slice(&[h, s, v]);
+        }
+        Ok(hsv_bytes)
+    }
+
+    // Inclusive (min, max) corners of a box of voxels
+    type VoxelBounds = ((usize, usize, usize), (usize, usize, usize));
+
     #[pyclass(name = "Raster")]
     #[derive(Clone)]
     struct Raster {
@@ -89,15 +318,103 @@ mod artnet_rs {
         transform: Vec<(usize, i32)>, // (axis, sign)
     }
 
+    impl Raster {
+        // Set every voxel in the inclusive box from `min` to `max` (logical x, y, z, so it goes
+        // through the orientation transform like set_pix), clipped to the raster bounds
+        fn fill_box(
+            &mut self,
+            min: (usize, usize, usize),
+            max: (usize, usize, usize),
+            color: &RGB,
+        ) {
+            let x_end = max.0.min(self.width - 1);
+            let y_end = max.1.min(self.height - 1);
+            let z_end = max.2.min(self.length - 1);
+            for z in min.2..=z_end {
+                for y in min.1..=y_end {
+                    for x in min.0..=x_end {
+                        let (tx, ty, tz) = self.transform_coords(x, y, z);
+                        let idx = ty * self.width + tx + tz * self.width * self.height;
+                        self.data[idx] = color.clone();
+                    }
+                }
+            }
+        }
+
+        // Storage indices of logical layer z, in row-major (y, then x) order
+        fn layer_indices(&self, z: usize) -> PyResult<Vec<usize>> {
+            if z >= self.length {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "z: {} length: {}",
+                    z, self.length
+                )));
+            }
+            let mut indices = Vec::with_capacity(self.width * self.height);
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let (tx, ty, tz) = self.transform_coords(x, y, z);
+                    indices.push(ty * self.width + tx + tz * self.width * self.height);
+                }
+            }
+            Ok(indices)
+        }
+
+        // Copy `src` into this raster with its origin at `offset`, both in logical coordinates
+        // so each raster's own orientation applies. `src` must fit entirely inside.
+        fn paste(&mut self, src: &Raster, offset: (usize, usize, usize)) -> Result<(), String> {
+            if offset.0 + src.width > self.width
+                || offset.1 + src.height > self.height
+                || offset.2 + src.length > self.length
+            {
+                return Err(format!(
+                    "{}x{}x{} at {:?} does not fit in {}x{}x{}",
+                    src.width, src.height, src.length, offset, self.width, self.height, self.length
+                ));
+            }
+            for z in 0..src.length {
+                for y in 0..src.height {
+                    for x in 0..src.width {
+                        let (sx, sy, sz) = src.transform_coords(x, y, z);
+                        let color = &src.data[sy * src.width + sx + sz * src.width * src.height];
+                        let (tx, ty, tz) =
+                            self.transform_coords(offset.0 + x, offset.1 + y, offset.2 + z);
+                        self.data[ty * self.width + tx + tz * self.width * self.height] =
+                            color.clone();
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
     #[pymethods]
     impl Raster {
         #[new]
+        #[pyo3(signature = (width, height, length, orientation=None, max_voxels=DEFAULT_MAX_RASTER_VOXELS))]
         fn new(
             width: usize,
             height: usize,
             length: usize,
             orientation: Option<Vec<String>>,
-        ) -> Self {
+            max_voxels: usize,
+        ) -> PyResult<Self> {
+            if width == 0 || height == 0 || length == 0 {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Raster dimensions must be non-zero (got {}x{}x{})",
+                    width, height, length
+                )));
+            }
+            let voxels = width
+                .checked_mul(height)
+                .and_then(|n| n.checked_mul(length))
+                .filter(|&n| n <= max_voxels)
+                .ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Raster {}x{}x{} exceeds the maximum of {} voxels",
+                        width, height, length, max_voxels
+                    ))
+                })?;
+
             let orientation = orientation
                 .unwrap_or_else(|| vec!["X".to_string(), "Y".to_string(), "Z".to_string()]);
             let mut raster = Raster {
@@ -105,12 +422,17 @@ mod artnet_rs {
                 height,
                 length,
                 brightness: 1.0,
-                data: vec![RGB::new(0, 0, 0); width * height * length],
+                data: vec![RGB::new(0, 0, 0); voxels],
                 orientation,
                 transform: Vec::new(),
             };
             raster.compute_transform();
-            raster
+            Ok(raster)
+        }
+
+        #[staticmethod]
+        fn default_max_voxels() -> usize {
+            DEFAULT_MAX_RASTER_VOXELS
         }
 
         fn compute_transform(&mut self) {
@@ -182,6 +504,67 @@ mod artnet_rs {
             self.data = vec![RGB::new(0, 0, 0); self.width * self.height * self.length];
         }
 
+        // Set every voxel to one color
+        fn fill(&mut self, color: RGB) {
+            self.data.fill(color);
+        }
+
+        // Fill the inclusive box (x0, y0, z0)-(x1, y1, z1) with a color, clipped to the bounds;
+        // replaces nested set_pix loops from Python
+        #[allow(clippy::too_many_arguments)]
+        fn fill_region(
+            &mut self,
+            x0: usize,
+            y0: usize,
+            z0: usize,
+            x1: usize,
+            y1: usize,
+            z1: usize,
+            color: RGB,
+        ) {
+            self.fill_box((x0, y0, z0), (x1, y1, z1), &color);
+        }
+
+        // Set the inclusive box (x0, y0, z0)-(x1, y1, z1) to black, clipped to the bounds
+        fn clear_region(
+            &mut self,
+            x0: usize,
+            y0: usize,
+            z0: usize,
+            x1: usize,
+            y1: usize,
+            z1: usize,
+        ) {
+            self.fill_box((x0, y0, z0), (x1, y1, z1), &RGB::new(0, 0, 0));
+        }
+
+        // The width*height voxels of logical layer z (through the transform), row by row
+        fn get_layer(&self, z: usize) -> PyResult<Vec<RGB>> {
+            Ok(self
+                .layer_indices(z)?
+                .into_iter()
+                .map(|idx| self.data[idx].clone())
+                .collect())
+        }
+
+        // Replace logical layer z with width*height voxels laid out like get_layer returns them
+        fn set_layer(&mut self, z: usize, data: Vec<RGB>) -> PyResult<()> {
+            let indices = self.layer_indices(z)?;
+            if data.len() != indices.len() {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Layer data has {} voxels, expected {} ({}x{})",
+                    data.len(),
+                    indices.len(),
+                    self.width,
+                    self.height
+                )));
+            }
+            for (idx, color) in indices.into_iter().zip(data) {
+                self.data[idx] = color;
+            }
+            Ok(())
+        }
+
         // Getters for Python compatibility
         fn get_width(&self) -> usize {
             self.width
@@ -192,6 +575,14 @@ mod artnet_rs {
         fn get_length(&self) -> usize {
             self.length
         }
+        // (width, height, length) in one call
+        fn dimensions(&self) -> (usize, usize, usize) {
+            (self.width, self.height, self.length)
+        }
+        // Total number of voxels
+        fn len(&self) -> usize {
+            self.width * self.height * self.length
+        }
         fn get_brightness(&self) -> f32 {
             self.brightness
         }
@@ -203,8 +594,84 @@ mod artnet_rs {
         }
 
         // Setters for Python compatibility
-        fn set_brightness(&mut self, brightness: f32) {
+        // Negative or non-finite values are rejected. Values above the sending controller's
+        // max_brightness are stored as given and clamped by send_dmx.
+        fn set_brightness(&mut self, brightness: f32) -> PyResult<()> {
+            check_brightness("brightness", brightness)?;
             self.brightness = brightness;
+            Ok(())
+        }
+
+        // Inclusive (min, max) logical coordinates of all non-black voxels, consistent with
+        // get_pix, or None if the raster is entirely black
+        fn content_bounds(&self) -> Option<VoxelBounds> {
+            let mut bounds: Option<VoxelBounds> = None;
+            for z in 0..self.length {
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let (tx, ty, tz) = self.transform_coords(x, y, z);
+                        let pixel =
+                            &self.data[ty * self.width + tx + tz * self.width * self.height];
+                        if pixel.red == 0 && pixel.green == 0 && pixel.blue == 0 {
+                            continue;
+                        }
+                        bounds = Some(match bounds {
+                            None => ((x, y, z), (x, y, z)),
+                            Some((min, max)) => (
+                                (min.0.min(x), min.1.min(y), min.2.min(z)),
+                                (max.0.max(x), max.1.max(y), max.2.max(z)),
+                            ),
+                        });
+                    }
+                }
+            }
+            bounds
+        }
+
+        // Content hash of the voxel data (RGB bytes in storage order), for frame dedup and tests
+        fn checksum(&self) -> u64 {
+            fnv1a_64(
+                self.data
+                    .iter()
+                    .flat_map(|pixel| [pixel.red, pixel.green, pixel.blue]),
+            )
+        }
+
+        // Bake a gamma curve into the raster so later reads see corrected colors
+        fn apply_gamma(&mut self, gamma: f32) -> PyResult<()> {
+            if !gamma.is_finite() || gamma <= 0.0 {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Gamma must be positive (got {})",
+                    gamma
+                )));
+            }
+            let lut = gamma_lut(gamma);
+            for pixel in &mut self.data {
+                pixel.red = lut[pixel.red as usize];
+                pixel.green = lut[pixel.green as usize];
+                pixel.blue = lut[pixel.blue as usize];
+            }
+            Ok(())
+        }
+
+        // Bake per-channel white-balance scaling into the raster
+        fn apply_balance(&mut self, r: f32, g: f32, b: f32) -> PyResult<()> {
+            if [r, g, b]
+                .iter()
+                .any(|scale| !scale.is_finite() || *scale < 0.0)
+            {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Balance factors must be non-negative (got {}, {}, {})",
+                    r, g, b
+                )));
+            }
+            let (red_lut, green_lut, blue_lut) = (balance_lut(r), balance_lut(g), balance_lut(b));
+            for pixel in &mut self.data {
+                pixel.red = red_lut[pixel.red as usize];
+                pixel.green = green_lut[pixel.green as usize];
+                pixel.blue = blue_lut[pixel.blue as usize];
+            }
+            Ok(())
         }
 
         // Direct access to data for compatibility with existing code
@@ -224,6 +691,17 @@ mod artnet_rs {
             Ok(self.data[idx].clone())
         }
 
+        // Get pixel without coordinate transformation (mirrors set_pix_direct)
+        fn get_pix_direct(&self, x: usize, y: usize, z: usize) -> PyResult<RGB> {
+            if x >= self.width || y >= self.height || z >= self.length {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Coordinates out of bounds",
+                ));
+            }
+            let idx = y * self.width + x + z * self.width * self.height;
+            Ok(self.data[idx].clone())
+        }
+
         // Set pixel without coordinate transformation (for direct access)
         fn set_pix_direct(&mut self, x: usize, y: usize, z: usize, color: RGB) -> PyResult<()> {
             if x >= self.width || y >= self.height || z >= self.length {
@@ -237,18 +715,211 @@ mod artnet_rs {
         }
     }
 
+    // (id, position, dimensions), as in the sender monitor's cube list
+    type CubeLayout = (String, (usize, usize, usize), (usize, usize, usize));
+
+    // Paste per-cube rasters into the world raster at their positions in `cubes`. Each cube
+    // raster must match its cube's dimensions; cubes without a raster are left as they are. Only
+    // voxel data is copied, so the world raster's brightness applies to every cube.
+    #[pyfunction]
+    fn composite_cubes(
+        mut world: PyRefMut<'_, Raster>,
+        cubes: Vec<CubeLayout>,
+        cube_rasters: HashMap<String, PyRef<'_, Raster>>,
+    ) -> PyResult<()> {
+        if let Some(unknown) = cube_rasters
+            .keys()
+            .find(|id| !cubes.iter().any(|(cube_id, _, _)| cube_id == *id))
+        {
+            return Err(PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                "No cube with id {} in the layout",
+                unknown
+            )));
+        }
+        for (id, position, dimensions) in &cubes {
+            let Some(raster) = cube_rasters.get(id) else {
+                continue;
+            };
+            if (raster.width, raster.height, raster.length) != *dimensions {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Raster for cube {} is {}x{}x{} but the cube is {}x{}x{}",
+                    id,
+                    raster.width,
+                    raster.height,
+                    raster.length,
+                    dimensions.0,
+                    dimensions.1,
+                    dimensions.2
+                )));
+            }
+            world.paste(raster, *position).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Cube {}: {}", id, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    // Delta transmission: when enabled, universes whose bytes match what was last sent on them
+    // are skipped, with an optional periodic full resend for nodes that missed an update
+    #[derive(Default)]
+    struct DeltaState {
+        enabled: bool,
+        full_resend_interval: Option<Duration>,
+        last_full_send: Option<Instant>,
+        force_full: bool,
+        // Whether the frame currently being sent must transmit every universe
+        current_frame_full: bool,
+        sent: HashMap<u16, Vec<u8>>,
+    }
+
+    impl DeltaState {
+        fn start_frame(&mut self, now: Instant) {
+            let resend_due = match (self.full_resend_interval, self.last_full_send) {
+                (Some(interval), Some(last)) => now.duration_since(last) >= interval,
+                _ => self.last_full_send.is_none(),
+            };
+            self.current_frame_full = !self.enabled || self.force_full || resend_due;
+            if self.enabled && self.current_frame_full {
+                self.last_full_send = Some(now);
+                self.force_full = false;
+            }
+        }
+
+        // Returns true if `chunk` should be sent on `universe`, caching it when delta is on
+        fn should_send(&mut self, universe: u16, chunk: &[u8]) -> bool {
+            if !self.enabled {
+                return true;
+            }
+            if !self.current_frame_full
+                && self.sent.get(&universe).map(Vec::as_slice) == Some(chunk)
+            {
+                return false;
+            }
+            self.sent.insert(universe, chunk.to_vec());
+            true
+        }
+    }
+
     #[pyclass(name = "ArtNetController")]
     struct ArtNetControllerRs {
         socket: UdpSocket,
         target_addr: String,
+        protocol_version: u16,
+        // Universes transmitted in the current frame so far, in send order
+        last_universes: Mutex<Vec<u16>>,
+        // Frame-rate cap: minimum time between frames (None = unlimited) and the last send time
+        min_frame_interval: Mutex<Option<Duration>>,
+        last_frame_sent: Mutex<Option<Instant>>,
+        // Whether the frame opened by the last begin_frame may be sent; None until begin_frame
+        // is first called, after which sends no longer start frames of their own
+        current_frame: Mutex<Option<bool>>,
+        // pace_frame clock: when the last frame returned and when the next one is due
+        pace_ticks: Mutex<Option<(Instant, Instant)>>,
+        // Cap on the brightness send_dmx applies, whatever the raster asks for
+        max_brightness: Mutex<f32>,
+        delta: Mutex<DeltaState>,
+        // Last bytes sent on each universe, kept only while capture is enabled
+        captured: Mutex<Option<HashMap<u16, Vec<u8>>>>,
     }
 
     impl ArtNetControllerRs {
+        // Claim the next frame slot, or return false if it arrives before the cap allows
+        fn try_start_frame(&self) -> bool {
+            let now = Instant::now();
+            let mut last_frame_sent = self.last_frame_sent.lock().unwrap();
+            if let (Some(min_interval), Some(last)) =
+                (*self.min_frame_interval.lock().unwrap(), *last_frame_sent)
+            {
+                if now.duration_since(last) < min_interval {
+                    return false;
+                }
+            }
+            *last_frame_sent = Some(now);
+            self.delta.lock().unwrap().start_frame(now);
+            self.last_universes.lock().unwrap().clear();
+            true
+        }
+
+        // Whether a send may go out: the answer begin_frame gave for the current frame, or
+        // without begin_frame, whether the send can claim a frame of its own
+        fn start_send(&self) -> bool {
+            let current_frame = *self.current_frame.lock().unwrap();
+            current_frame.unwrap_or_else(|| self.try_start_frame())
+        }
+
+        // Layers are spaced universes_per_layer apart, so each must fit in that many universes
+        fn check_layer_fits(
+            width: usize,
+            height: usize,
+            channels_per_universe: usize,
+            universes_per_layer: u16,
+        ) -> PyResult<()> {
+            if channels_per_universe == 0 {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "channels_per_universe must be greater than 0",
+                ));
+            }
+            // Otherwise a pixel's channels would be split across two universes
+            if !channels_per_universe.is_multiple_of(CHANNELS_PER_PIXEL) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "channels_per_universe must be a multiple of {} (got {}, try {})",
+                    CHANNELS_PER_PIXEL,
+                    channels_per_universe,
+                    (channels_per_universe / CHANNELS_PER_PIXEL).max(1) * CHANNELS_PER_PIXEL
+                )));
+            }
+            let required = (width * height * CHANNELS_PER_PIXEL).div_ceil(channels_per_universe);
+            if required > universes_per_layer as usize {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "universes_per_layer is {} but a {}x{} layer needs {} universes of {} channels; adjacent layers would overlap",
+                    universes_per_layer, width, height, required, channels_per_universe
+                )));
+            }
+            Ok(())
+        }
+
+        // Universes are computed in u16, so a layer past the Art-Net range would wrap onto the
+        // universes of earlier layers. `z_indices` defaults to every channel_span-th layer.
+        #[allow(clippy::too_many_arguments)]
+        fn check_universe_range(
+            base_universe: u16,
+            width: usize,
+            height: usize,
+            length: usize,
+            channels_per_universe: usize,
+            universes_per_layer: u16,
+            channel_span: usize,
+            z_indices: Option<&[usize]>,
+        ) -> PyResult<()> {
+            if channel_span == 0 {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "channel_span must be greater than 0",
+                ));
+            }
+            let layers = z_indices.map_or_else(|| length.div_ceil(channel_span), <[usize]>::len);
+            let layer_universes =
+                (width * height * CHANNELS_PER_PIXEL).div_ceil(channels_per_universe);
+            if let Some((out_z, universe)) = universe_overflow(
+                base_universe,
+                layer_universes,
+                universes_per_layer,
+                channel_span,
+                layers,
+            ) {
+                let z = z_indices.map_or(out_z * channel_span, |z_indices| z_indices[out_z]);
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "layer {} would be sent on universe {}, past the Art-Net maximum of {} (base_universe {}, universes_per_layer {})",
+                    z, universe, MAX_ARTNET_UNIVERSE, base_universe, universes_per_layer
+                )));
+            }
+            Ok(())
+        }
+
         fn create_dmx_packet(&self, universe: u16, data: &[u8]) -> Vec<u8> {
             let mut packet = Vec::with_capacity(18 + data.len());
             packet.extend_from_slice(b"Art-Net\x00");
             packet.extend_from_slice(&0x5000u16.to_le_bytes()); // OpDmx
-            packet.extend_from_slice(&14u16.to_be_bytes()); // ProtVer
+            packet.extend_from_slice(&self.protocol_version.to_be_bytes()); // ProtVer
             packet.push(0); // Sequence
             packet.push(0); // Physical
             packet.extend_from_slice(&universe.to_le_bytes());
@@ -257,11 +928,85 @@ mod artnet_rs {
             packet
         }
 
+        // Split one layer's bytes into consecutive universes starting at `universe`
+        fn send_layer(
+            &self,
+            mut universe: u16,
+            data: &[u8],
+            channels_per_universe: usize,
+        ) -> PyResult<()> {
+            let mut last_universes = self.last_universes.lock().unwrap();
+            let mut delta = self.delta.lock().unwrap();
+            let mut captured = self.captured.lock().unwrap();
+            let mut data_to_send = data;
+            while !data_to_send.is_empty() {
+                let chunk_size = std::cmp::min(data_to_send.len(), channels_per_universe);
+                let chunk = &data_to_send[..chunk_size];
+                if delta.should_send(universe, chunk) {
+                    let dmx_packet = self.create_dmx_packet(universe, chunk);
+                    self.socket.send_to(&dmx_packet, &self.target_addr)?;
+                    last_universes.push(universe);
+                    if let Some(captured) = captured.as_mut() {
+                        captured.insert(universe, chunk.to_vec());
+                    }
+                }
+
+                data_to_send = &data_to_send[chunk_size..];
+                universe += 1;
+            }
+            Ok(())
+        }
+
+        // Fast path for rasters whose data is already packed as (z, y, x, rgb) bytes
+        #[allow(clippy::too_many_arguments)]
+        fn send_dmx_packed_bytes(
+            &self,
+            base_universe: u16,
+            width: usize,
+            height: usize,
+            brightness: f32,
+            pixels: &[u8],
+            channels_per_universe: usize,
+            universes_per_layer: u16,
+            channel_span: usize,
+            z_indices: &[usize],
+        ) -> PyResult<()> {
+            let brightness = brightness.min(*self.max_brightness.lock().unwrap());
+            let layer_size = width * height * 3;
+            let mut data_bytes = Vec::with_capacity(layer_size);
+
+            for (out_z, &z) in z_indices.iter().enumerate() {
+                let universe = (out_z / channel_span) as u16 * universes_per_layer + base_universe;
+
+                let start = z * layer_size;
+                let end = start + layer_size;
+
+                if end > pixels.len() {
+                    continue;
+                }
+
+                let layer = &pixels[start..end];
+                if brightness == 1.0 {
+                    data_bytes.extend_from_slice(layer);
+                } else {
+                    data_bytes.extend(layer.iter().map(|&c| saturate_u8(c as f32 * brightness)));
+                }
+
+                self.send_layer(universe, &data_bytes, channels_per_universe)?;
+                data_bytes.clear();
+            }
+
+            let sync_packet = self.create_sync_packet();
+            self.socket.send_to(&sync_packet, &self.target_addr)?;
+
+            Ok(())
+        }
+
         fn create_sync_packet(&self) -> Vec<u8> {
             let mut packet = Vec::with_capacity(14);
             packet.extend_from_slice(b"Art-Net\x00");
             packet.extend_from_slice(&0x5200u16.to_le_bytes()); // OpSync
-            packet.extend_from_slice(&14u16.to_be_bytes()); // ProtVer
+            packet.extend_from_slice(&self.protocol_version.to_be_bytes()); // ProtVer
             packet.push(0); // Aux1
             packet.push(0); // Aux2
             packet
@@ -271,13 +1016,46 @@ mod artnet_rs {
     #[pymethods]
     impl ArtNetControllerRs {
         #[new]
-        fn new(ip: String, port: u16) -> PyResult<Self> {
-            let socket = UdpSocket::bind("0.0.0.0:0")?;
-            socket.set_broadcast(true)?;
+        // `port` is the destination UDP port (Art-Net nodes normally listen on 6454).
+        // `protocol_version` overrides the ProtVer field for nodes that expect another revision.
+        #[pyo3(signature = (ip, port, broadcast=None, bind_address=None, capture=false, protocol_version=DEFAULT_ARTNET_PROTOCOL_VERSION))]
+        fn new(
+            ip: String,
+            port: u16,
+            broadcast: Option<bool>,
+            bind_address: Option<String>,
+            capture: bool,
+            protocol_version: u16,
+        ) -> PyResult<Self> {
+            if port == 0 {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Art-Net destination port must be between 1 and 65535",
+                ));
+            }
+            let socket = bind_artnet_socket(bind_address.as_deref()).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::InvalidInput {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+                } else {
+                    e.into()
+                }
+            })?;
+            // Only request SO_BROADCAST when needed; auto-detect unless told explicitly
+            if broadcast.unwrap_or_else(|| is_broadcast_ip(&ip)) {
+                socket.set_broadcast(true)?;
+            }
             let target_addr = format!("{}:{}", ip, port);
             Ok(ArtNetControllerRs {
                 socket,
                 target_addr,
+                protocol_version,
+                last_universes: Mutex::new(Vec::new()),
+                min_frame_interval: Mutex::new(None),
+                last_frame_sent: Mutex::new(None),
+                current_frame: Mutex::new(None),
+                pace_ticks: Mutex::new(None),
+                max_brightness: Mutex::new(DEFAULT_MAX_BRIGHTNESS),
+                delta: Mutex::new(DeltaState::default()),
+                captured: Mutex::new(capture.then(HashMap::new)),
             })
         }
 
@@ -286,6 +1064,10 @@ mod artnet_rs {
             self.target_addr.split(':').next().unwrap_or("").to_string()
         }
 
+        fn get_protocol_version(&self) -> u16 {
+            self.protocol_version
+        }
+
         fn get_port(&self) -> u16 {
             // Extract port from target_addr (format: "ip:port")
             self.target_addr
@@ -296,6 +1078,253 @@ mod artnet_rs {
                 .unwrap_or(0)
         }
 
+        // Cap the frame rate; frames starting sooner are dropped. A frame is everything sent
+        // between begin_frame calls, or each send when begin_frame isn't used. None or 0 removes
+        // the cap.
+        #[pyo3(signature = (fps=None))]
+        fn set_max_fps(&self, fps: Option<f64>) -> PyResult<()> {
+            let min_interval = match fps {
+                None | Some(0.0) => None,
+                Some(fps) if fps.is_finite() && fps > 0.0 => {
+                    Some(Duration::from_secs_f64(1.0 / fps))
+                }
+                Some(fps) => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "max fps must be a positive number, got {}",
+                        fps
+                    )))
+                }
+            };
+            *self.min_frame_interval.lock().unwrap() = min_interval;
+            Ok(())
+        }
+
+        // Start a frame covering every send until the next begin_frame, for callers that send
+        // several rasters through one controller per frame. Returns false if set_max_fps
+        // throttles the frame, in which case its sends return false without transmitting.
+        fn begin_frame(&self) -> bool {
+            let granted = self.try_start_frame();
+            *self.current_frame.lock().unwrap() = Some(granted);
+            granted
+        }
+
+        fn get_max_brightness(&self) -> f32 {
+            *self.max_brightness.lock().unwrap()
+        }
+
+        // The one brightness cap: send_dmx clamps any raster's brightness (Rust or Python) to
+        // this, 1.0 by default. Raise it to allow over-driving content.
+        fn set_max_brightness(&self, max_brightness: f32) -> PyResult<()> {
+            check_brightness("max_brightness", max_brightness)?;
+            *self.max_brightness.lock().unwrap() = max_brightness;
+            Ok(())
+        }
+
+        fn get_max_fps(&self) -> Option<f64> {
+            self.min_frame_interval
+                .lock()
+                .unwrap()
+                .map(|interval| 1.0 / interval.as_secs_f64())
+        }
+
+        // Sleep until the next frame is due at target_hz and return the seconds since the
+        // previous call (0.0 on the first). Deadlines advance by whole periods so sleep overshoot
+        // doesn't accumulate; the GIL is released while sleeping.
+        fn pace_frame(&self, py: Python<'_>, target_hz: f64) -> PyResult<f64> {
+            if !(target_hz.is_finite() && target_hz > 0.0) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "target_hz must be a positive number, got {}",
+                    target_hz
+                )));
+            }
+            let period = Duration::from_secs_f64(1.0 / target_hz);
+            let previous = *self.pace_ticks.lock().unwrap();
+            let Some((last_tick, deadline)) = previous else {
+                let now = Instant::now();
+                *self.pace_ticks.lock().unwrap() = Some((now, now + period));
+                return Ok(0.0);
+            };
+
+            let now = Instant::now();
+            if deadline > now {
+                py.allow_threads(|| std::thread::sleep(deadline - now));
+            }
+            let tick = Instant::now();
+            *self.pace_ticks.lock().unwrap() =
+                Some((tick, next_pace_deadline(deadline, tick, period)));
+            Ok(tick.duration_since(last_tick).as_secs_f64())
+        }
+
+        // Only transmit universes whose content changed since they were last sent. The ArtSync
+        // is always sent. `full_resend_interval` (seconds) periodically forces a complete frame.
+        #[pyo3(signature = (enabled, full_resend_interval=None))]
+        fn set_delta_mode(&self, enabled: bool, full_resend_interval: Option<f64>) -> PyResult<()> {
+            let full_resend_interval = match full_resend_interval {
+                None => None,
+                Some(secs) if secs.is_finite() && secs > 0.0 => Some(Duration::from_secs_f64(secs)),
+                Some(secs) => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "full_resend_interval must be a positive number of seconds, got {}",
+                        secs
+                    )))
+                }
+            };
+            // Start from a clean cache so the first delta frame is complete
+            *self.delta.lock().unwrap() = DeltaState {
+                enabled,
+                full_resend_interval,
+                ..DeltaState::default()
+            };
+            Ok(())
+        }
+
+        fn is_delta_mode(&self) -> bool {
+            self.delta.lock().unwrap().enabled
+        }
+
+        // Send every universe on the next frame, e.g. after a node reboots
+        fn force_full_resend(&self) {
+            self.delta.lock().unwrap().force_full = true;
+        }
+
+        // Universes the current frame has been sent on, for checking that controllers don't
+        // overlap. The list starts over at each begin_frame (or each send without begin_frame).
+        fn last_universes_sent(&self) -> Vec<u16> {
+            self.last_universes.lock().unwrap().clone()
+        }
+
+        // Retain the last bytes sent on each universe for verification; disabling drops them
+        fn set_capture(&self, enabled: bool) {
+            let mut captured = self.captured.lock().unwrap();
+            if enabled != captured.is_some() {
+                *captured = enabled.then(HashMap::new);
+            }
+        }
+
+        fn is_capturing(&self) -> bool {
+            self.captured.lock().unwrap().is_some()
+        }
+
+        // DMX channel data last sent on `universe`, if capture is on and it has been sent
+        fn get_last_universe_data(&self, universe: u16) -> Option<Vec<u8>> {
+            self.captured
+                .lock()
+                .unwrap()
+                .as_ref()
+                .and_then(|captured| captured.get(&universe).cloned())
+        }
+
+        // Send a named pattern sized to width x height x length, for checking wiring and
+        // addressing without building a Raster. See test_pattern_bytes for the patterns; `step`
+        // positions the moving pixel. Returns false if the frame is throttled by set_max_fps.
+        #[allow(clippy::too_many_arguments)]
+        #[pyo3(signature = (pattern, base_universe, width, height, length, step=0, channels_per_universe=510, universes_per_layer=3))]
+        fn send_test_pattern(
+            &self,
+            pattern: &str,
+            base_universe: u16,
+            width: usize,
+            height: usize,
+            length: usize,
+            step: usize,
+            channels_per_universe: usize,
+            universes_per_layer: u16,
+        ) -> PyResult<bool> {
+            Self::check_layer_fits(width, height, channels_per_universe, universes_per_layer)?;
+            Self::check_universe_range(
+                base_universe,
+                width,
+                height,
+                length,
+                channels_per_universe,
+                universes_per_layer,
+                1,
+                None,
+            )?;
+            let pixels = test_pattern_bytes(
+                pattern,
+                width,
+                height,
+                length,
+                channels_per_universe,
+                universes_per_layer,
+                step,
+            )
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+
+            if !self.start_send() {
+                return Ok(false);
+            }
+
+            let z_indices: Vec<usize> = (0..length).collect();
+            self.send_dmx_packed_bytes(
+                base_universe,
+                width,
+                height,
+                1.0,
+                &pixels,
+                channels_per_universe,
+                universes_per_layer,
+                1,
+                &z_indices,
+            )?;
+            Ok(true)
+        }
+
+        // Kill switch: immediately send zeros on every universe a width x height x length
+        // geometry uses (laid out like send_dmx), then a sync. Ignores the raster, the fps cap and
+        // delta mode, and releases the GIL so it can be called while a render loop is stuck.
+        #[allow(clippy::too_many_arguments)]
+        #[pyo3(signature = (base_universe, width, height, length, channels_per_universe=510, universes_per_layer=3, channel_span=1))]
+        fn blackout(
+            &self,
+            py: Python<'_>,
+            base_universe: u16,
+            width: usize,
+            height: usize,
+            length: usize,
+            channels_per_universe: usize,
+            universes_per_layer: u16,
+            channel_span: usize,
+        ) -> PyResult<()> {
+            Self::check_layer_fits(width, height, channels_per_universe, universes_per_layer)?;
+            Self::check_universe_range(
+                base_universe,
+                width,
+                height,
+                length,
+                channels_per_universe,
+                universes_per_layer,
+                channel_span,
+                None,
+            )?;
+            let layer_size = width * height * 3;
+            let layers = (0..length).step_by(channel_span).count();
+            py.allow_threads(|| {
+                let zeros = vec![0u8; channels_per_universe.min(layer_size)];
+                for out_z in 0..layers {
+                    let mut universe =
+                        (out_z / channel_span) as u16 * universes_per_layer + base_universe;
+                    let mut remaining = layer_size;
+                    while remaining > 0 {
+                        let chunk_size = remaining.min(channels_per_universe);
+                        let dmx_packet = self.create_dmx_packet(universe, &zeros[..chunk_size]);
+                        self.socket.send_to(&dmx_packet, &self.target_addr)?;
+                        remaining -= chunk_size;
+                        universe += 1;
+                    }
+                }
+                let sync_packet = self.create_sync_packet();
+                self.socket.send_to(&sync_packet, &self.target_addr)?;
+                // Delta mode must not skip the next frame as unchanged; a send holding the lock
+                // is left alone so the blackout never waits on it
+                if let Ok(mut delta) = self.delta.try_lock() {
+                    delta.force_full = true;
+                }
+                Ok(())
+            })
+        }
+
         #[pyo3(signature = (base_universe, raster, channels_per_universe=510, universes_per_layer=3, channel_span=1, z_indices=None))]
         fn send_dmx(
             &self,
@@ -305,17 +1334,38 @@ mod artnet_rs {
             universes_per_layer: u16,
             channel_span: usize,
             z_indices: Option<Vec<usize>>,
-        ) -> PyResult<()> {
+        ) -> PyResult<bool> {
+            let width: usize = raster.getattr("width")?.extract()?;
+            let height: usize = raster.getattr("height")?.extract()?;
+            let length: usize = raster.getattr("length")?.extract()?;
+            let brightness: f32 = raster.getattr("brightness")?.extract()?;
+            check_brightness("brightness", brightness)?;
+            // Rasters store any non-negative brightness; set_max_brightness caps it here
+            let brightness = brightness.min(*self.max_brightness.lock().unwrap());
+
+            Self::check_layer_fits(width, height, channels_per_universe, universes_per_layer)?;
+            Self::check_universe_range(
+                base_universe,
+                width,
+                height,
+                length,
+                channels_per_universe,
+                universes_per_layer,
+                channel_span,
+                z_indices.as_deref(),
+            )?;
+
+            // Returns false without sending if the frame is throttled by set_max_fps
+            if !self.start_send() {
+                return Ok(false);
+            }
+
             // Check if this is a Rust Raster by looking for a specific method
             if raster.hasattr("get_data_mut")? {
                 // This is likely a Rust Raster, try to get its data directly
-                let width: usize = raster.getattr("width")?.extract()?;
-                let height: usize = raster.getattr("height")?.extract()?;
-                let length: usize = raster.getattr("length")?.extract()?;
-                let brightness: f32 = raster.getattr("brightness")?.extract()?;
                 let data: Vec<RGB> = raster.call_method0("get_data_mut")?.extract()?;
 
-                return self.send_dmx_rust_raster_data(
+                self.send_dmx_rust_raster_data(
                     base_universe,
                     width,
                     height,
@@ -326,16 +1376,12 @@ mod artnet_rs {
                     universes_per_layer,
                     channel_span,
                     z_indices,
-                );
+                )?;
+                return Ok(true);
             }
 
             // Fall back to Python raster
-            let width: usize = raster.getattr("width")?.extract()?;
-            let height: usize = raster.getattr("height")?.extract()?;
-            let length: usize = raster.getattr("length")?.extract()?;
-            let brightness: f32 = raster.getattr("brightness")?.extract()?;
             let raster_data_attr = raster.getattr("data")?;
-            let raster_data: &Bound<'_, PyList> = raster_data_attr.downcast()?;
 
             let z_indices_vec: Vec<usize>;
             let z_indices_ref: &[usize] = match z_indices {
@@ -346,11 +1392,48 @@ mod artnet_rs {
                 }
             };
 
+            // Contiguous byte buffers (bytes, or numpy arrays via tobytes()) are copied
+            // directly instead of reflecting over each RGB object
+            let packed = if let Ok(bytes) = raster_data_attr.downcast::<PyBytes>() {
+                Some(bytes.clone())
+            } else if raster_data_attr.hasattr("tobytes")? {
+                raster_data_attr
+                    .call_method0("tobytes")?
+                    .downcast_into::<PyBytes>()
+                    .ok()
+            } else {
+                None
+            };
+
+            if let Some(packed) = packed {
+                let expected = width * height * length * 3;
+                if packed.as_bytes().len() != expected {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Raster data is {} bytes, expected {} (uint8 RGB)",
+                        packed.as_bytes().len(),
+                        expected
+                    )));
+                }
+                self.send_dmx_packed_bytes(
+                    base_universe,
+                    width,
+                    height,
+                    brightness,
+                    packed.as_bytes(),
+                    channels_per_universe,
+                    universes_per_layer,
+                    channel_span,
+                    z_indices_ref,
+                )?;
+                return Ok(true);
+            }
+
+            let raster_data: &Bound<'_, PyList> = raster_data_attr.downcast()?;
+
             let mut data_bytes = Vec::with_capacity(width * height * 3);
 
             for (out_z, &z) in z_indices_ref.iter().enumerate() {
-                let mut universe =
-                    (out_z / channel_span) as u16 * universes_per_layer + base_universe;
+                let universe = (out_z / channel_span) as u16 * universes_per_layer + base_universe;
 
                 let start = z * width * height;
                 let end = (z + 1) * width * height;
@@ -372,23 +1455,14 @@ mod artnet_rs {
                     data_bytes.push(saturate_u8(b * brightness));
                 }
 
-                let mut data_to_send = &data_bytes[..];
-                while !data_to_send.is_empty() {
-                    let chunk_size = std::cmp::min(data_to_send.len(), channels_per_universe);
-                    let chunk = &data_to_send[..chunk_size];
-                    let dmx_packet = self.create_dmx_packet(universe, chunk);
-                    self.socket.send_to(&dmx_packet, &self.target_addr)?;
-
-                    data_to_send = &data_to_send[chunk_size..];
-                    universe += 1;
-                }
+                self.send_layer(universe, &data_bytes, channels_per_universe)?;
                 data_bytes.clear();
             }
 
             let sync_packet = self.create_sync_packet();
             self.socket.send_to(&sync_packet, &self.target_addr)?;
 
-            Ok(())
+            Ok(true)
         }
 
         fn send_dmx_rust_raster_data(
@@ -416,8 +1490,7 @@ mod artnet_rs {
             let mut data_bytes = Vec::with_capacity(width * height * 3);
 
             for (out_z, &z) in z_indices_ref.iter().enumerate() {
-                let mut universe =
-                    (out_z / channel_span) as u16 * universes_per_layer + base_universe;
+                let universe = (out_z / channel_span) as u16 * universes_per_layer + base_universe;
 
                 let start = z * width * height;
                 let end = (z + 1) * width * height;
@@ -433,16 +1506,7 @@ mod artnet_rs {
                     data_bytes.push(saturate_u8(rgb.blue as f32 * brightness));
                 }
 
-                let mut data_to_send = &data_bytes[..];
-                while !data_to_send.is_empty() {
-                    let chunk_size = std::cmp::min(data_to_send.len(), channels_per_universe);
-                    let chunk = &data_to_send[..chunk_size];
-                    let dmx_packet = self.create_dmx_packet(universe, chunk);
-                    self.socket.send_to(&dmx_packet, &self.target_addr)?;
-
-                    data_to_send = &data_to_send[chunk_size..];
-                    universe += 1;
-                }
+                self.send_layer(universe, &data_bytes, channels_per_universe)?;
                 data_bytes.clear();
             }
 
@@ -452,4 +1516,183 @@ mod artnet_rs {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod raster_tests {
+        use super::*;
+
+        #[test]
+        fn test_fill_region_whole_volume_matches_fill() {
+            let orientation = vec!["-X".to_string(), "Y".to_string(), "-Z".to_string()];
+            let mut filled = Raster::new(4, 3, 2, Some(orientation.clone()), 1000).unwrap();
+            let mut region = Raster::new(4, 3, 2, Some(orientation), 1000).unwrap();
+
+            filled.fill(RGB::new(10, 20, 30));
+            // Bounds past the edge are clipped
+            region.fill_region(0, 0, 0, 10, 10, 10, RGB::new(10, 20, 30));
+            assert_eq!(region.checksum(), filled.checksum());
+
+            region.clear_region(1, 1, 1, 1, 1, 1);
+            assert_eq!(region.get_pix(1, 1, 1).unwrap().red, 0);
+            assert_eq!(region.get_pix(0, 1, 1).unwrap().red, 10);
+        }
+
+        #[test]
+        fn test_layer_round_trip_follows_orientation() {
+            let orientation = vec!["-X".to_string(), "Y".to_string(), "-Z".to_string()];
+            let mut raster = Raster::new(4, 3, 2, Some(orientation), 1000).unwrap();
+            raster.set_pix(3, 2, 1, RGB::new(1, 2, 3)).unwrap();
+
+            let mut layer = raster.get_layer(1).unwrap();
+            assert_eq!(layer.len(), 12);
+            assert_eq!(layer[2 * 4 + 3].blue, 3);
+
+            layer[0] = RGB::new(9, 9, 9);
+            raster.set_layer(1, layer).unwrap();
+            assert_eq!(raster.get_pix(0, 0, 1).unwrap().red, 9);
+            assert_eq!(raster.get_pix(0, 0, 0).unwrap().red, 0);
+            assert!(raster.set_layer(0, vec![RGB::new(0, 0, 0); 11]).is_err());
+        }
+
+        #[test]
+        fn test_paste_places_cube_at_offset() {
+            let mut world = Raster::new(4, 4, 2, None, 1000).unwrap();
+            let orientation = vec!["-X".to_string(), "Y".to_string(), "Z".to_string()];
+            let mut cube = Raster::new(2, 2, 1, Some(orientation), 1000).unwrap();
+            cube.set_pix(1, 0, 0, RGB::new(7, 7, 7)).unwrap();
+
+            world.paste(&cube, (2, 2, 1)).unwrap();
+            assert_eq!(world.get_pix(3, 2, 1).unwrap().red, 7);
+            assert_eq!(world.get_pix(2, 2, 1).unwrap().red, 0);
+            assert!(world.paste(&cube, (3, 0, 0)).is_err());
+        }
+
+        #[test]
+        fn test_content_bounds_uses_logical_coordinates() {
+            let orientation = vec!["-X".to_string(), "-Y".to_string(), "Z".to_string()];
+            let mut raster = Raster::new(5, 4, 3, Some(orientation), 1000).unwrap();
+            assert_eq!(raster.content_bounds(), None);
+
+            raster.set_pix(1, 3, 0, RGB::new(0, 0, 1)).unwrap();
+            raster.set_pix(4, 1, 2, RGB::new(1, 0, 0)).unwrap();
+            assert_eq!(raster.content_bounds(), Some(((1, 1, 0), (4, 3, 2))));
+        }
+    }
+
+    #[cfg(test)]
+    mod controller_tests {
+        use super::*;
+
+        fn test_controller() -> ArtNetControllerRs {
+            ArtNetControllerRs::new(
+                "127.0.0.1".to_string(),
+                6454,
+                Some(false),
+                Some("127.0.0.1".to_string()),
+                false,
+                DEFAULT_ARTNET_PROTOCOL_VERSION,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_fps_cap_throttles_frames_not_sends() {
+            let controller = test_controller();
+            controller.set_max_fps(Some(1.0)).unwrap();
+
+            // Without begin_frame each send is a frame, so a second send is throttled
+            assert!(controller.start_send());
+            assert!(!controller.start_send());
+
+            // Every send of a granted frame goes out, and none of a throttled one
+            *controller.last_frame_sent.lock().unwrap() = None;
+            assert!(controller.begin_frame());
+            assert!(controller.start_send());
+            assert!(controller.start_send());
+            assert!(!controller.begin_frame());
+            assert!(!controller.start_send());
+        }
+
+        #[test]
+        fn test_last_universes_cover_the_whole_frame() {
+            let controller = test_controller();
+            assert!(controller.begin_frame());
+            controller.send_layer(0, &[0; 6], 3).unwrap();
+            controller.send_layer(10, &[0; 3], 3).unwrap();
+            assert_eq!(controller.last_universes_sent(), vec![0, 1, 10]);
+
+            assert!(controller.begin_frame());
+            assert!(controller.last_universes_sent().is_empty());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_color_correction_is_identity() {
+        let identity: Vec<u8> = (0..=255).collect();
+        assert_eq!(gamma_lut(1.0).to_vec(), identity);
+        assert_eq!(balance_lut(1.0).to_vec(), identity);
+    }
+
+    #[test]
+    fn test_pace_deadline_keeps_schedule_unless_far_behind() {
+        let start = Instant::now();
+        let period = Duration::from_millis(10);
+        let overslept = start + Duration::from_millis(2);
+        assert_eq!(next_pace_deadline(start, overslept, period), start + period);
+
+        let stalled = start + Duration::from_millis(35);
+        assert_eq!(next_pace_deadline(start, stalled, period), stalled + period);
+    }
+
+    #[test]
+    fn test_universe_overflow_finds_first_layer_past_range() {
+        // 3 universes per layer from 32700: layer 22 starts at 32766 and needs 32768
+        assert_eq!(universe_overflow(32700, 3, 3, 1, 22), None);
+        assert_eq!(universe_overflow(32700, 3, 3, 1, 30), Some((22, 32768)));
+        // Layers sharing a universe group with channel_span overflow together
+        assert_eq!(universe_overflow(32700, 3, 3, 2, 45), Some((44, 32768)));
+        // Past u16::MAX instead of wrapping to a low universe
+        assert_eq!(universe_overflow(65000, 1, 1, 1, 1000), Some((0, 65000)));
+    }
+
+    #[test]
+    fn test_fnv1a_64_known_values() {
+        assert_eq!(fnv1a_64([]), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_64(*b"a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(fnv1a_64([1, 0, 0]), fnv1a_64([0, 1, 0]));
+    }
+
+    #[test]
+    fn test_pattern_generation() {
+        assert_eq!(
+            test_pattern_bytes("red", 2, 1, 1, 510, 1, 0).unwrap(),
+            vec![255, 0, 0, 255, 0, 0]
+        );
+
+        let moving = test_pattern_bytes("moving_pixel", 2, 2, 1, 510, 1, 5).unwrap();
+        assert_eq!(moving, vec![0, 0, 0, 255, 255, 255, 0, 0, 0, 0, 0, 0]);
+
+        // Two voxels per universe: each universe gets its own color
+        let rainbow = test_pattern_bytes("rainbow", 4, 1, 1, 6, 2, 0).unwrap();
+        assert_eq!(rainbow[0..3], rainbow[3..6]);
+        assert_ne!(rainbow[0..3], rainbow[6..9]);
+
+        assert!(test_pattern_bytes("plaid", 1, 1, 1, 510, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_color_correction_luts() {
+        let gamma = gamma_lut(2.2);
+        assert_eq!((gamma[0], gamma[255]), (0, 255));
+        assert!(gamma[128] < 128);
+
+        let half = balance_lut(0.5);
+        assert_eq!((half[0], half[200], half[255]), (0, 100, 128));
+        assert_eq!(balance_lut(2.0)[200], 255);
+    }
 }
@@ -0,0 +1,128 @@
+use axum::{response::Html, routing::get, Router};
+use control_port_rs::control_port::ControlPortManager;
+use sender_monitor_rs::sender_monitor::SenderMonitor;
+use std::sync::Arc;
+
+// Serves the control-port and sender web monitors from one listener, under /control and
+// /sender. The standalone servers are unchanged for setups that want them apart.
+pub struct CombinedMonitor {
+    control_monitor: control_port_rs::web_monitor::WebMonitor,
+    sender_monitor: sender_monitor_rs::web_monitor::WebMonitor,
+    bind_address: String,
+}
+
+impl CombinedMonitor {
+    pub fn new(
+        control_manager: Arc<ControlPortManager>,
+        sender_monitor: Arc<SenderMonitor>,
+    ) -> Self {
+        Self {
+            control_monitor: control_port_rs::web_monitor::WebMonitor::new(control_manager),
+            sender_monitor: sender_monitor_rs::web_monitor::WebMonitor::new(sender_monitor),
+            bind_address: "0.0.0.0".to_string(),
+        }
+    }
+
+    pub fn with_log_buffer_size(mut self, size: usize) -> Self {
+        self.control_monitor = self.control_monitor.with_log_buffer_size(size);
+        self
+    }
+
+    pub fn with_bind_address(mut self, bind_address: String) -> Self {
+        self.bind_address = bind_address;
+        self
+    }
+
+    pub fn create_router(&self) -> Router {
+        Router::new()
+            .route("/", get(index_html))
+            .nest("/control", self.control_monitor.create_router())
+            .nest("/sender", self.sender_monitor.create_router())
+    }
+
+    pub async fn start_server(
+        &self,
+        port: u16,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let app = self.create_router();
+
+        let bind_addr = format!("{}:{}", self.bind_address, port);
+        let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+
+        println!(
+            "Combined monitor server running on http://{}:{} (/control, /sender)",
+            self.bind_address, port
+        );
+
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+}
+
+async fn index_html() -> Html<&'static str> {
+    Html(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Monitors</title>
+</head>
+<body style="font-family: Arial, sans-serif; padding: 20px;">
+    <h1>Monitors</h1>
+    <ul>
+        <li><a href="/control">Control ports</a></li>
+        <li><a href="/sender">ArtNet sender</a></li>
+    </ul>
+</body>
+</html>"#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use control_port_rs::control_port::Config;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Status line of a plain HTTP/1.0 GET
+    async fn get_status_line(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.0\r\nHost: localhost\r\n\r\n", path).as_bytes())
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let response = String::from_utf8_lossy(&response);
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_both_monitors() {
+        let control_manager = Arc::new(ControlPortManager::new(Config {
+            controller_addresses: std::collections::HashMap::new(),
+        }));
+        let monitor = CombinedMonitor::new(control_manager, Arc::new(SenderMonitor::new()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = monitor.create_router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        for path in [
+            "/",
+            "/control",
+            "/control/api/control_ports",
+            "/sender",
+            "/sender/api/stats",
+            "/sender/health",
+        ] {
+            let status_line = get_status_line(addr, path).await;
+            assert!(
+                status_line.contains(" 200 "),
+                "GET {}: {}",
+                path,
+                status_line
+            );
+        }
+    }
+}
@@ -7,20 +7,101 @@ use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
-use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
-use tokio::time::{interval, timeout};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
+use tokio::time::{interval, interval_at, timeout};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 // use uuid::Uuid;
 
+// Button events buffered per subscriber before slow receivers start lagging
+pub const DEFAULT_BUTTON_CHANNEL_CAPACITY: usize = 100;
+
+fn default_button_channel_capacity() -> usize {
+    DEFAULT_BUTTON_CHANNEL_CAPACITY
+}
+
+// How long the committed display may differ from what was sent before it is resent
+pub const DEFAULT_DISPLAY_RESYNC_SECS: u64 = 5;
+
+fn default_display_resync_secs() -> u64 {
+    DEFAULT_DISPLAY_RESYNC_SECS
+}
+
+// TCP connect timeout, and the wait between failed connection attempts
+pub const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 2000;
+pub const DEFAULT_RECONNECT_INTERVAL_MS: u64 = 2000;
+
+fn default_connect_timeout_ms() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_MS
+}
+
+fn default_reconnect_interval_ms() -> u64 {
+    DEFAULT_RECONNECT_INTERVAL_MS
+}
+
+// Each reconnect wait is randomized by up to this fraction either way, so controllers that
+// dropped together don't all reconnect on the same tick
+pub const DEFAULT_RECONNECT_JITTER: f64 = 0.25;
+
+fn default_reconnect_jitter() -> f64 {
+    DEFAULT_RECONNECT_JITTER
+}
+
+// `interval_ms` scaled by 1 +/- `jitter` (clamped to 0..=1), picked by `sample` across the
+// whole u64 range
+fn jittered_reconnect_delay(interval_ms: u64, jitter: f64, sample: u64) -> Duration {
+    let jitter = if jitter.is_finite() {
+        jitter.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let unit = sample as f64 / u64::MAX as f64;
+    let factor = 1.0 + jitter * (2.0 * unit - 1.0);
+    Duration::from_millis(((interval_ms as f64 * factor).round() as u64).max(1))
+}
+
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    // RandomState is seeded randomly per instance, which is plenty for spreading reconnects
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+// Wire format for LED frames. Both carry a little-endian u16 LED count followed by RGB
+// triplets; Raw sends those bytes as-is after `ledraw:` (the count gives the frame length, so
+// newlines in the payload are fine) for firmware that can skip base64 decoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedEncoding {
+    #[default]
+    Base64,
+    Raw,
+}
+
 // Configuration structures
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ControllerConfig {
     pub ip: String,
     pub port: u16,
+    #[serde(default = "default_button_channel_capacity")]
+    pub button_channel_capacity: usize,
+    // 0 disables display resync
+    #[serde(default = "default_display_resync_secs")]
+    pub display_resync_secs: u64,
+    #[serde(default)]
+    pub led_encoding: LedEncoding,
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    #[serde(default = "default_reconnect_interval_ms")]
+    pub reconnect_interval_ms: u64,
+    // Fraction of reconnect_interval_ms to randomize each wait by; 0 disables
+    #[serde(default = "default_reconnect_jitter")]
+    pub reconnect_jitter: f64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -28,6 +109,14 @@ pub struct Config {
     pub controller_addresses: std::collections::HashMap<String, ControllerConfig>,
 }
 
+// Hardware capabilities reported by newer firmware in its controller identification
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ControllerCapabilities {
+    pub lcd_width: Option<u16>,
+    pub lcd_height: Option<u16>,
+    pub led_count: Option<usize>,
+}
+
 // Message types for communication with controllers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IncomingMessage {
@@ -35,59 +124,99 @@ pub enum IncomingMessage {
     Heartbeat,
     Controller {
         dip: String,
+        version: Option<String>,
+        capabilities: Option<ControllerCapabilities>,
     },
     Button {
         buttons: Vec<bool>,
+        // Analog inputs (e.g. pots) reported alongside the buttons, if the firmware has any
+        analog: Option<Vec<f32>>,
+    },
+    // Well-formed JSON that doesn't match a built-in message, e.g. from custom firmware
+    Raw {
+        json: serde_json::Value,
     },
 }
 
 impl IncomingMessage {
+    // Built-in messages (a "buttons" key, or a "type" of heartbeat or controller) must be well
+    // formed; only other JSON objects are passed through as Raw
     pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
         // Parse the JSON first
         let json_value: serde_json::Value = serde_json::from_str(json_str)?;
+        let invalid = |reason: &str| {
+            serde_json::Error::io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{}: {}", reason, json_str),
+            ))
+        };
 
         // Check for button messages first (most common)
         if let Some(buttons) = json_value.get("buttons") {
+            let analog = json_value
+                .get("analog")
+                .and_then(|analog| serde_json::from_value::<Vec<f32>>(analog.clone()).ok());
+
             // Handle both boolean and integer button values
             if let Ok(buttons_vec) = serde_json::from_value::<Vec<bool>>(buttons.clone()) {
                 return Ok(IncomingMessage::Button {
                     buttons: buttons_vec,
+                    analog,
                 });
             } else if let Ok(buttons_vec) = serde_json::from_value::<Vec<i32>>(buttons.clone()) {
                 // Convert integers to booleans (0 = false, non-zero = true)
                 let bool_buttons: Vec<bool> = buttons_vec.iter().map(|&x| x != 0).collect();
                 return Ok(IncomingMessage::Button {
                     buttons: bool_buttons,
+                    analog,
                 });
             }
+            return Err(invalid(
+                "Button message needs an array of booleans or integers",
+            ));
         }
 
         // Check for messages with type field
         if let Some(msg_type) = json_value.get("type") {
-            if let Some(type_str) = msg_type.as_str() {
-                match type_str {
-                    "heartbeat" => {
-                        return Ok(IncomingMessage::Heartbeat);
-                    }
-                    "controller" => {
-                        if let Some(dip) = json_value.get("dip") {
-                            if let Some(dip_str) = dip.as_str() {
-                                return Ok(IncomingMessage::Controller {
-                                    dip: dip_str.to_string(),
-                                });
-                            }
-                        }
-                    }
-                    _ => {}
+            let type_str = msg_type
+                .as_str()
+                .ok_or_else(|| invalid("Message type must be a string"))?;
+            match type_str {
+                "heartbeat" => {
+                    return Ok(IncomingMessage::Heartbeat);
+                }
+                "controller" => {
+                    let dip_str = json_value
+                        .get("dip")
+                        .and_then(|dip| dip.as_str())
+                        .ok_or_else(|| invalid("Controller message needs a string dip"))?;
+                    // Older firmware only reports the DIP; version and capabilities are
+                    // optional and ignored if malformed
+                    let version = json_value.get("version").and_then(|v| match v {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    });
+                    let capabilities = json_value
+                        .get("capabilities")
+                        .and_then(|c| serde_json::from_value(c.clone()).ok());
+                    return Ok(IncomingMessage::Controller {
+                        dip: dip_str.to_string(),
+                        version,
+                        capabilities,
+                    });
                 }
+                _ => {}
             }
         }
 
+        // Surface any other JSON object so callers can handle custom message types
+        if json_value.is_object() {
+            return Ok(IncomingMessage::Raw { json: json_value });
+        }
+
         // If we get here, we couldn't parse the message
-        Err(serde_json::Error::io(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("Unknown message format: {}", json_str),
-        )))
+        Err(invalid("Unknown message format"))
     }
 }
 
@@ -101,7 +230,20 @@ pub enum OutgoingMessage {
 }
 
 impl OutgoingMessage {
+    pub fn log_kind(&self) -> LogKind {
+        match self {
+            OutgoingMessage::Noop => LogKind::Noop,
+            OutgoingMessage::LcdClear | OutgoingMessage::LcdWrite { .. } => LogKind::Lcd,
+            OutgoingMessage::Backlight { .. } => LogKind::Backlight,
+            OutgoingMessage::Led { .. } => LogKind::Led,
+        }
+    }
+
     pub fn to_bytes(&self) -> Bytes {
+        self.to_bytes_with_led_encoding(LedEncoding::Base64)
+    }
+
+    pub fn to_bytes_with_led_encoding(&self, led_encoding: LedEncoding) -> Bytes {
         match self {
             OutgoingMessage::Noop => Bytes::from("noop\n"),
             OutgoingMessage::LcdClear => Bytes::from("lcd:clear\n"),
@@ -122,8 +264,19 @@ impl OutgoingMessage {
                 for (r, g, b) in rgb_values {
                     payload.extend_from_slice(&[*r, *g, *b]);
                 }
-                let encoded = general_purpose::STANDARD.encode(&payload);
-                Bytes::from(format!("led:{}\n", encoded))
+                match led_encoding {
+                    LedEncoding::Base64 => {
+                        let encoded = general_purpose::STANDARD.encode(&payload);
+                        Bytes::from(format!("led:{}\n", encoded))
+                    }
+                    LedEncoding::Raw => {
+                        let mut frame = Vec::with_capacity(8 + payload.len());
+                        frame.extend_from_slice(b"ledraw:");
+                        frame.extend_from_slice(&payload);
+                        frame.push(b'\n');
+                        Bytes::from(frame)
+                    }
+                }
             }
         }
     }
@@ -134,11 +287,38 @@ impl OutgoingMessage {
 pub struct LogEntry {
     pub timestamp: DateTime<Utc>,
     pub direction: LogDirection,
+    pub kind: LogKind,
     pub message: String,
     pub raw_data: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+// What a log entry is about, so logs can be filtered without matching on message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogKind {
+    Heartbeat,
+    Noop,
+    Button,
+    Lcd,
+    Led,
+    Backlight,
+    Connection,
+    // Controller identification (DIP, firmware version, capabilities)
+    Controller,
+    // Unrecognized JSON messages passed through to subscribers
+    Message,
+    ParseError,
+    Other,
+}
+
+impl LogKind {
+    // Keepalive traffic that's usually hidden from log views
+    pub fn is_keepalive(self) -> bool {
+        matches!(self, LogKind::Heartbeat | LogKind::Noop)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogDirection {
     Incoming,
@@ -160,10 +340,18 @@ pub struct ControllerStats {
     pub bytes_received: u64,
     pub messages_sent: u64,
     pub messages_received: u64,
+    // Messages queued for the controller but not yet written; growth means it has stalled
+    pub pending_messages: usize,
     pub connection_attempts: u64,
+    pub parse_errors: u64,
+    pub disconnect_count: u64,
+    pub current_uptime_seconds: Option<i64>,
+    pub firmware_version: Option<String>,
+    pub capabilities: Option<ControllerCapabilities>,
     pub last_error: Option<String>,
     pub throughput_sent_bps: f64,
     pub throughput_received_bps: f64,
+    pub messages_received_per_sec: f64,
     pub last_throughput_update: Option<DateTime<Utc>>,
     pub last_heartbeat_received: Option<DateTime<Utc>>,
     pub last_noop_sent: Option<DateTime<Utc>>,
@@ -187,6 +375,115 @@ impl ControllerStats {
     }
 }
 
+static PANIC_HOOK: Once = Once::new();
+
+// Log panics from controller tasks once per process, chaining to whatever hook was already
+// installed (e.g. by the embedding app) instead of replacing it
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            error!("Panic: {:?}", panic_info);
+            previous_hook(panic_info);
+        }));
+    });
+}
+
+// Stats younger than this are served from the cached snapshot instead of being recomputed
+const STATS_SNAPSHOT_TTL_MS: i64 = 100;
+
+// Interval between intermediate frames of an LED fade (~30 fps)
+const LED_FADE_FRAME_MS: u32 = 33;
+
+// Upper bounds on the LCD and LED sizes a controller may report, so a bogus identification
+// can't make us allocate huge buffers
+const MAX_LCD_WIDTH: u16 = 80;
+const MAX_LCD_HEIGHT: u16 = 16;
+const MAX_LED_COUNT: usize = 1024;
+
+// Linearly blend two LED frames; LEDs missing from `from` start out dark
+fn interpolate_leds(from: &[(u8, u8, u8)], to: &[(u8, u8, u8)], t: f32) -> Vec<(u8, u8, u8)> {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    to.iter()
+        .enumerate()
+        .map(|(i, &(r, g, b))| {
+            let (fr, fg, fb) = from.get(i).copied().unwrap_or((0, 0, 0));
+            (lerp(fr, r), lerp(fg, g), lerp(fb, b))
+        })
+        .collect()
+}
+
+// Greedy word wrap where the first line has `first_width` columns and the rest `width`.
+// A word that only fits on a full line moves down (leaving the first line empty), words longer
+// than a full line are hard-wrapped, and explicit newlines start a new line.
+fn wrap_text(text: &str, first_width: usize, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for paragraph in text.split('\n') {
+        for word in paragraph.split(' ').filter(|word| !word.is_empty()) {
+            let mut word: Vec<char> = word.chars().collect();
+            loop {
+                let line_width = if lines.is_empty() { first_width } else { width };
+                let separator = usize::from(current_len > 0);
+                if current_len + separator + word.len() <= line_width {
+                    if separator > 0 {
+                        current.push(' ');
+                    }
+                    current.extend(&word);
+                    current_len += separator + word.len();
+                    break;
+                }
+                if current_len > 0 || (line_width < width && word.len() <= width) {
+                    lines.push(std::mem::take(&mut current));
+                    current_len = 0;
+                    continue;
+                }
+                let rest = word.split_off(line_width);
+                lines.push(word.into_iter().collect());
+                word = rest;
+            }
+        }
+        lines.push(std::mem::take(&mut current));
+        current_len = 0;
+    }
+
+    lines
+}
+
+// Receive the next button state from a broadcast. Each message is a full snapshot, so after a
+// lag the skipped states are stale: jump to the newest one the channel still holds instead of
+// replaying old presses/releases. Only fails once the channel is closed.
+pub async fn recv_button_state(
+    rx: &mut broadcast::Receiver<Vec<bool>>,
+) -> Result<Vec<bool>, broadcast::error::RecvError> {
+    loop {
+        match rx.recv().await {
+            Ok(buttons) => return Ok(buttons),
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                warn!(
+                    "Button receiver lagged by {} messages, resyncing to latest state",
+                    n
+                );
+                let mut latest = None;
+                loop {
+                    match rx.try_recv() {
+                        Ok(buttons) => latest = Some(buttons),
+                        Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+                if let Some(buttons) = latest {
+                    return Ok(buttons);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 // Controller state management
 #[derive(Debug)]
 pub struct ControllerState {
@@ -199,11 +496,18 @@ pub struct ControllerState {
     pub bytes_received: AtomicU64,
     pub messages_sent: AtomicU64,
     pub messages_received: AtomicU64,
+    // Depth of the outgoing queue; the unbounded channel can't report its own length
+    pub pending_messages: AtomicUsize,
     pub connection_attempts: AtomicU64,
+    pub parse_errors: AtomicU64,
+    pub disconnect_count: AtomicU64,
+    // When the last connection closed, used to report downtime on reconnect
+    pub disconnected_at: Arc<RwLock<Option<DateTime<Utc>>>>,
 
     // Throughput tracking
     pub last_bytes_sent: AtomicU64,
     pub last_bytes_received: AtomicU64,
+    pub last_messages_received: AtomicU64,
     pub last_throughput_update: Arc<RwLock<Option<DateTime<Utc>>>>,
 
     // Heartbeat tracking
@@ -212,16 +516,31 @@ pub struct ControllerState {
     pub heartbeat_received_active: Arc<RwLock<bool>>,
     pub noop_sent_active: Arc<RwLock<bool>>,
 
-    // Display buffer management
-    pub display_width: u16,
-    pub display_height: u16,
+    // Firmware version and capabilities from the controller identification, if reported
+    pub firmware_version: Arc<RwLock<Option<String>>>,
+    pub capabilities: Arc<RwLock<Option<ControllerCapabilities>>>,
+
+    // Display buffer management; dimensions default to 20x4 until the controller reports its LCD
+    pub display_width: AtomicU16,
+    pub display_height: AtomicU16,
     pub front_buffer: Arc<RwLock<Vec<Vec<char>>>>,
     pub back_buffer: Arc<RwLock<Vec<Vec<char>>>>,
 
+    // What the LCD shows according to the LCD messages written on the current connection;
+    // None until a clear has been written, since the controller's contents are unknown
+    pub sent_display: Arc<RwLock<Option<Vec<Vec<char>>>>>,
+    pub display_out_of_sync_since: Arc<RwLock<Option<DateTime<Utc>>>>,
+
+    // Last LED frame sent, used as the starting point for fades
+    pub led_buffer: Arc<RwLock<Vec<(u8, u8, u8)>>>,
+    pub led_fade_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+
     // Communication channels
     pub message_tx: Arc<Mutex<mpsc::UnboundedSender<OutgoingMessage>>>,
     pub message_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<OutgoingMessage>>>>,
     pub button_broadcast: broadcast::Sender<Vec<bool>>,
+    pub analog_broadcast: broadcast::Sender<Vec<f32>>,
+    pub raw_message_broadcast: broadcast::Sender<serde_json::Value>,
 
     // Internal task handles
     pub connection_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
@@ -230,7 +549,9 @@ pub struct ControllerState {
 impl ControllerState {
     pub fn new(dip: String, config: ControllerConfig) -> Self {
         let (message_tx, message_rx) = mpsc::unbounded_channel();
-        let (button_broadcast, _) = broadcast::channel(100);
+        let (button_broadcast, _) = broadcast::channel(config.button_channel_capacity.max(1));
+        let (analog_broadcast, _) = broadcast::channel(config.button_channel_capacity.max(1));
+        let (raw_message_broadcast, _) = broadcast::channel(100);
 
         let stats = ControllerStats {
             dip: dip.clone(),
@@ -243,10 +564,17 @@ impl ControllerState {
             bytes_received: 0,
             messages_sent: 0,
             messages_received: 0,
+            pending_messages: 0,
             connection_attempts: 0,
+            parse_errors: 0,
+            disconnect_count: 0,
+            current_uptime_seconds: None,
+            firmware_version: None,
+            capabilities: None,
             last_error: None,
             throughput_sent_bps: 0.0,
             throughput_received_bps: 0.0,
+            messages_received_per_sec: 0.0,
             last_throughput_update: None,
             last_heartbeat_received: None,
             last_noop_sent: None,
@@ -269,21 +597,34 @@ impl ControllerState {
             bytes_received: AtomicU64::new(0),
             messages_sent: AtomicU64::new(0),
             messages_received: AtomicU64::new(0),
+            pending_messages: AtomicUsize::new(0),
             connection_attempts: AtomicU64::new(0),
+            parse_errors: AtomicU64::new(0),
+            disconnect_count: AtomicU64::new(0),
+            disconnected_at: Arc::new(RwLock::new(None)),
             last_bytes_sent: AtomicU64::new(0),
             last_bytes_received: AtomicU64::new(0),
+            last_messages_received: AtomicU64::new(0),
             last_throughput_update: Arc::new(RwLock::new(None)),
             last_heartbeat_received: Arc::new(RwLock::new(None)),
             last_noop_sent: Arc::new(RwLock::new(None)),
             heartbeat_received_active: Arc::new(RwLock::new(false)),
             noop_sent_active: Arc::new(RwLock::new(false)),
-            display_width: width as u16,
-            display_height: height as u16,
+            firmware_version: Arc::new(RwLock::new(None)),
+            capabilities: Arc::new(RwLock::new(None)),
+            display_width: AtomicU16::new(width as u16),
+            display_height: AtomicU16::new(height as u16),
             front_buffer: Arc::new(RwLock::new(front_buffer)),
             back_buffer: Arc::new(RwLock::new(back_buffer)),
+            sent_display: Arc::new(RwLock::new(None)),
+            display_out_of_sync_since: Arc::new(RwLock::new(None)),
+            led_buffer: Arc::new(RwLock::new(Vec::new())),
+            led_fade_task: Arc::new(Mutex::new(None)),
             message_tx: Arc::new(Mutex::new(message_tx)),
             message_rx: Arc::new(RwLock::new(Some(message_rx))),
             button_broadcast,
+            analog_broadcast,
+            raw_message_broadcast,
             connection_task: Arc::new(RwLock::new(None)),
         }
     }
@@ -291,12 +632,14 @@ impl ControllerState {
     pub async fn add_log(
         &self,
         direction: LogDirection,
+        kind: LogKind,
         message: String,
         raw_data: Option<String>,
     ) {
         let entry = LogEntry {
             timestamp: Utc::now(),
             direction,
+            kind,
             message,
             raw_data,
         };
@@ -310,14 +653,43 @@ impl ControllerState {
         }
     }
 
+    // Log how long the controller was down if this connection follows a disconnect
+    pub async fn log_reconnect(&self) {
+        let Some(disconnected_at) = self.disconnected_at.write().await.take() else {
+            return;
+        };
+        let down_secs = (Utc::now() - disconnected_at).num_seconds().max(0);
+        self.add_log(
+            LogDirection::Info,
+            LogKind::Connection,
+            format!("Reconnected after {}s down", down_secs),
+            None,
+        )
+        .await;
+    }
+
     pub async fn update_stats(&self) {
         let mut stats = self.stats.write().await;
         stats.bytes_sent = self.bytes_sent.load(Ordering::Relaxed);
         stats.bytes_received = self.bytes_received.load(Ordering::Relaxed);
         stats.messages_sent = self.messages_sent.load(Ordering::Relaxed);
         stats.messages_received = self.messages_received.load(Ordering::Relaxed);
+        stats.pending_messages = self.pending_messages.load(Ordering::Relaxed);
         stats.connection_attempts = self.connection_attempts.load(Ordering::Relaxed);
+        stats.parse_errors = self.parse_errors.load(Ordering::Relaxed);
+        stats.disconnect_count = self.disconnect_count.load(Ordering::Relaxed);
         stats.connected = *self.connected.read().await;
+        stats.firmware_version = self.firmware_version.read().await.clone();
+        stats.capabilities = self.capabilities.read().await.clone();
+
+        // Uptime only counts while the current connection is up
+        stats.current_uptime_seconds = if stats.connected {
+            stats
+                .connection_time
+                .map(|time| (Utc::now() - time).num_seconds())
+        } else {
+            None
+        };
 
         // Update heartbeat status
         let last_heartbeat_received = self.last_heartbeat_received.read().await;
@@ -354,10 +726,25 @@ impl ControllerState {
         }
     }
 
+    // Recompute stats only when the cached snapshot is older than STATS_SNAPSHOT_TTL_MS,
+    // so frequent pollers mostly take the read lock
+    pub async fn refresh_stats_if_stale(&self) {
+        let fresh = self
+            .stats
+            .read()
+            .await
+            .last_throughput_update
+            .is_some_and(|time| (Utc::now() - time).num_milliseconds() < STATS_SNAPSHOT_TTL_MS);
+        if !fresh {
+            self.update_stats().await;
+        }
+    }
+
     async fn update_throughput(&self, stats: &mut ControllerStats) {
         let now = Utc::now();
         let current_bytes_sent = self.bytes_sent.load(Ordering::Relaxed);
         let current_bytes_received = self.bytes_received.load(Ordering::Relaxed);
+        let current_messages_received = self.messages_received.load(Ordering::Relaxed);
 
         if let Some(last_update) = stats.last_throughput_update {
             let time_diff = (now - last_update).num_milliseconds() as f64 / 1000.0;
@@ -366,6 +753,7 @@ impl ControllerState {
                 // Only update if at least 100ms have passed
                 let last_sent = self.last_bytes_sent.load(Ordering::Relaxed);
                 let last_received = self.last_bytes_received.load(Ordering::Relaxed);
+                let last_messages = self.last_messages_received.load(Ordering::Relaxed);
 
                 // Calculate instantaneous throughput (bytes per second)
                 let instant_sent_bps = if time_diff > 0.0 {
@@ -380,6 +768,9 @@ impl ControllerState {
                     0.0
                 };
 
+                let instant_messages_per_sec =
+                    current_messages_received.saturating_sub(last_messages) as f64 / time_diff;
+
                 // First-order low-pass filter with time constant of 2 seconds
                 let alpha = time_diff / (2.0 + time_diff); // Time constant = 2 seconds
 
@@ -387,12 +778,16 @@ impl ControllerState {
                     alpha * instant_sent_bps + (1.0 - alpha) * stats.throughput_sent_bps;
                 stats.throughput_received_bps =
                     alpha * instant_received_bps + (1.0 - alpha) * stats.throughput_received_bps;
+                stats.messages_received_per_sec = alpha * instant_messages_per_sec
+                    + (1.0 - alpha) * stats.messages_received_per_sec;
 
                 // Update last values for next calculation
                 self.last_bytes_sent
                     .store(current_bytes_sent, Ordering::Relaxed);
                 self.last_bytes_received
                     .store(current_bytes_received, Ordering::Relaxed);
+                self.last_messages_received
+                    .store(current_messages_received, Ordering::Relaxed);
             }
         } else {
             // First time update, initialize
@@ -400,42 +795,131 @@ impl ControllerState {
                 .store(current_bytes_sent, Ordering::Relaxed);
             self.last_bytes_received
                 .store(current_bytes_received, Ordering::Relaxed);
+            self.last_messages_received
+                .store(current_messages_received, Ordering::Relaxed);
         }
 
         stats.last_throughput_update = Some(now);
     }
 
-    pub async fn clear_display(&self) {
+    // Apply the version and capabilities from a controller identification, resizing the display
+    // and LED buffers to the reported hardware
+    pub async fn apply_capabilities(
+        &self,
+        version: Option<String>,
+        capabilities: Option<ControllerCapabilities>,
+    ) {
+        *self.firmware_version.write().await = version;
+
+        if let Some(caps) = &capabilities {
+            if caps.lcd_width.is_some_and(|width| width > MAX_LCD_WIDTH)
+                || caps
+                    .lcd_height
+                    .is_some_and(|height| height > MAX_LCD_HEIGHT)
+                || caps.led_count.is_some_and(|count| count > MAX_LED_COUNT)
+            {
+                warn!(
+                    "Controller reported {:?}; limiting to {}x{} LCD and {} LEDs",
+                    caps, MAX_LCD_WIDTH, MAX_LCD_HEIGHT, MAX_LED_COUNT
+                );
+            }
+            let width = caps
+                .lcd_width
+                .unwrap_or(self.display_width.load(Ordering::Relaxed))
+                .min(MAX_LCD_WIDTH);
+            let height = caps
+                .lcd_height
+                .unwrap_or(self.display_height.load(Ordering::Relaxed))
+                .min(MAX_LCD_HEIGHT);
+            self.resize_display(width, height).await;
+
+            if let Some(led_count) = caps.led_count {
+                self.led_buffer
+                    .write()
+                    .await
+                    .resize(led_count.min(MAX_LED_COUNT), (0, 0, 0));
+            }
+        }
+
+        *self.capabilities.write().await = capabilities;
+    }
+
+    // Resize both display buffers, keeping whatever content still fits. Code holding both
+    // buffers always locks front_buffer before back_buffer.
+    pub async fn resize_display(&self, width: u16, height: u16) {
+        let mut front_buffer = self.front_buffer.write().await;
         let mut back_buffer = self.back_buffer.write().await;
-        for y in 0..self.display_height as usize {
-            for x in 0..self.display_width as usize {
-                back_buffer[y][x] = ' ';
+        for buffer in [&mut *front_buffer, &mut *back_buffer] {
+            buffer.resize(height as usize, Vec::new());
+            for row in buffer.iter_mut() {
+                row.resize(width as usize, ' ');
             }
         }
+        self.display_width.store(width, Ordering::Relaxed);
+        self.display_height.store(height, Ordering::Relaxed);
+    }
+
+    pub async fn clear_display(&self) {
+        let mut back_buffer = self.back_buffer.write().await;
+        for row in back_buffer.iter_mut() {
+            row.fill(' ');
+        }
+    }
+
+    // The committed display contents, one string per row
+    pub async fn get_display_snapshot(&self) -> Vec<String> {
+        self.front_buffer
+            .read()
+            .await
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect()
+    }
+
+    // Blank a single row so the next commit only has to update that line
+    pub async fn clear_line(&self, y: u16) {
+        if let Some(row) = self.back_buffer.write().await.get_mut(y as usize) {
+            row.fill(' ');
+        }
     }
 
     pub async fn write_display(&self, x: u16, y: u16, text: &str) {
-        if y >= self.display_height || x >= self.display_width {
+        let mut back_buffer = self.back_buffer.write().await;
+        let Some(row) = back_buffer.get_mut(y as usize) else {
             return;
+        };
+
+        // Text running past the end of the row is clipped
+        for (cell, ch) in row.iter_mut().skip(x as usize).zip(text.chars()) {
+            *cell = ch;
         }
+    }
 
+    // Like write_display, but text that doesn't fit continues on the following rows (from
+    // column 0), word-wrapped where possible. Text past the bottom row is dropped.
+    pub async fn write_display_wrap(&self, x: u16, y: u16, text: &str) {
         let mut back_buffer = self.back_buffer.write().await;
-        let chars: Vec<char> = text.chars().collect();
-        let y = y as usize;
-        let mut x = x as usize;
+        let width = back_buffer.first().map_or(0, Vec::len);
+        let x = x as usize;
+        if x >= width {
+            return;
+        }
 
-        for ch in chars {
-            if x >= self.display_width as usize {
+        for (i, line) in wrap_text(text, width - x, width).iter().enumerate() {
+            let Some(row) = back_buffer.get_mut(y as usize + i) else {
                 break;
+            };
+            let start = if i == 0 { x } else { 0 };
+            for (cell, ch) in row.iter_mut().skip(start).zip(line.chars()) {
+                *cell = ch;
             }
-            back_buffer[y][x] = ch;
-            x += 1;
         }
     }
 
     pub async fn commit_display(&self) -> Result<Vec<OutgoingMessage>> {
         let mut messages = Vec::new();
-        let front_buffer = self.front_buffer.read().await;
+        // Same lock order as resize_display, and no read-to-write upgrade while back_buffer is held
+        let mut front_buffer = self.front_buffer.write().await;
         let back_buffer = self.back_buffer.read().await;
 
         // Check if back buffer is all spaces - if so, send clear
@@ -445,18 +929,14 @@ impl ControllerState {
 
         if all_spaces {
             messages.push(OutgoingMessage::LcdClear);
-            drop(front_buffer);
-            let mut front_buffer = self.front_buffer.write().await;
-            for y in 0..self.display_height as usize {
-                for x in 0..self.display_width as usize {
-                    front_buffer[y][x] = ' ';
-                }
+            for row in front_buffer.iter_mut() {
+                row.fill(' ');
             }
             return Ok(messages);
         }
 
         // Find differences and send updates
-        for y in 0..self.display_height as usize {
+        for y in 0..back_buffer.len() {
             let changes = self.find_contiguous_changes(&front_buffer, &back_buffer, y);
             for (start, end) in changes {
                 let text: String = back_buffer[y][start..end].iter().collect();
@@ -469,13 +949,7 @@ impl ControllerState {
         }
 
         // Update front buffer
-        drop(front_buffer);
-        let mut front_buffer = self.front_buffer.write().await;
-        for y in 0..self.display_height as usize {
-            for x in 0..self.display_width as usize {
-                front_buffer[y][x] = back_buffer[y][x];
-            }
-        }
+        front_buffer.clone_from(&back_buffer);
 
         Ok(messages)
     }
@@ -489,8 +963,9 @@ impl ControllerState {
         let mut changes: Vec<(usize, usize)> = Vec::new();
         let mut start = None;
         let mut last_change_end = None;
+        let width = back_buffer[y].len();
 
-        for x in 0..self.display_width as usize {
+        for x in 0..width {
             if front_buffer[y][x] != back_buffer[y][x] {
                 if start.is_none() {
                     // If within 3 chars of previous change, extend previous change
@@ -498,7 +973,7 @@ impl ControllerState {
                         let distance = x - end;
                         if distance <= 3 && !changes.is_empty() {
                             // Ensure we don't go beyond buffer bounds
-                            let new_end = (x + 1).min(self.display_width as usize);
+                            let new_end = (x + 1).min(width);
                             changes.last_mut().unwrap().1 = new_end;
                             last_change_end = Some(new_end);
                             continue;
@@ -514,7 +989,7 @@ impl ControllerState {
         }
 
         if let Some(s) = start {
-            changes.push((s, self.display_width as usize));
+            changes.push((s, width));
         }
 
         changes
@@ -527,23 +1002,91 @@ impl ControllerState {
             *self.noop_sent_active.write().await = true;
         }
 
+        if let OutgoingMessage::Led { rgb_values } = &message {
+            *self.led_buffer.write().await = rgb_values.clone();
+        }
+
+        // Counted before sending so the I/O task can't dequeue it first
         let tx_guard = self.message_tx.lock().await;
-        tx_guard
-            .send(message)
-            .map_err(|e| anyhow!("Failed to send message: {}", e))?;
+        self.pending_messages.fetch_add(1, Ordering::Relaxed);
+        if let Err(e) = tx_guard.send(message) {
+            self.message_dequeued();
+            return Err(anyhow!("Failed to send message: {}", e));
+        }
         Ok(())
     }
 
+    fn message_dequeued(&self) {
+        let _ = self
+            .pending_messages
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_sub(1))
+            });
+    }
+
+    // Set LEDs immediately, cancelling any fade in progress
+    pub async fn set_leds(&self, rgb_values: Vec<(u8, u8, u8)>) -> Result<()> {
+        if let Some(task) = self.led_fade_task.lock().await.take() {
+            task.abort();
+        }
+        self.send_message(OutgoingMessage::Led { rgb_values }).await
+    }
+
+    // Fade from the current LED frame to `target` over `duration_ms`, driving the intermediate
+    // frames from a background task. A new fade or set_leds call replaces a running fade.
+    pub async fn fade_leds(self: &Arc<Self>, target: Vec<(u8, u8, u8)>, duration_ms: u32) {
+        let mut fade_task = self.led_fade_task.lock().await;
+        if let Some(task) = fade_task.take() {
+            task.abort();
+        }
+
+        let start = self.led_buffer.read().await.clone();
+        let steps = (duration_ms / LED_FADE_FRAME_MS).max(1);
+        let controller = self.clone();
+        *fade_task = Some(tokio::spawn(async move {
+            // The first frame goes out one period in, so the last lands at duration_ms
+            let period = Duration::from_millis(LED_FADE_FRAME_MS as u64);
+            let mut frame_interval = interval_at(tokio::time::Instant::now() + period, period);
+            for step in 1..=steps {
+                frame_interval.tick().await;
+                let rgb_values = interpolate_leds(&start, &target, step as f32 / steps as f32);
+                if let Err(e) = controller
+                    .send_message(OutgoingMessage::Led { rgb_values })
+                    .await
+                {
+                    controller
+                        .add_log(
+                            LogDirection::Error,
+                            LogKind::Led,
+                            format!("LED fade failed: {}", e),
+                            None,
+                        )
+                        .await;
+                    break;
+                }
+            }
+        }));
+    }
+
     pub async fn force_display_refresh(&self) -> Result<()> {
         // Force a complete display refresh by sending all non-empty lines
         let back_buffer = self.back_buffer.read().await;
+        self.send_display_contents(&back_buffer).await
+    }
 
+    // Resend the committed display, for when the controller has drifted from it
+    pub async fn resync_display(&self) -> Result<()> {
+        let front_buffer = self.front_buffer.read().await.clone();
+        self.send_display_contents(&front_buffer).await
+    }
+
+    async fn send_display_contents(&self, buffer: &[Vec<char>]) -> Result<()> {
         // First clear the display
         self.send_message(OutgoingMessage::LcdClear).await?;
 
         // Then send all non-empty lines
-        for y in 0..self.display_height as usize {
-            let line: String = back_buffer[y].iter().collect();
+        for (y, row) in buffer.iter().enumerate() {
+            let line: String = row.iter().collect();
             let trimmed = line.trim();
             if !trimmed.is_empty() {
                 // Find the first non-space character
@@ -562,11 +1105,56 @@ impl ControllerState {
 
         Ok(())
     }
+
+    // Track LCD messages once they have been written to the socket
+    pub async fn record_sent_display(&self, message: &OutgoingMessage) {
+        match message {
+            OutgoingMessage::LcdClear => {
+                let front_buffer = self.front_buffer.read().await;
+                let width = front_buffer.first().map_or(0, Vec::len);
+                *self.sent_display.write().await = Some(vec![vec![' '; width]; front_buffer.len()]);
+            }
+            OutgoingMessage::LcdWrite { x, y, text } => {
+                if let Some(sent) = self.sent_display.write().await.as_mut() {
+                    if let Some(row) = sent.get_mut(*y as usize) {
+                        for (cell, ch) in row.iter_mut().skip(*x as usize).zip(text.chars()) {
+                            *cell = ch;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // True once the committed display has differed from what was sent for at least
+    // `max_divergence`. Short divergences are normal while a commit is in flight.
+    pub async fn display_needs_resync(&self, max_divergence: Duration) -> bool {
+        let in_sync = {
+            let front_buffer = self.front_buffer.read().await;
+            self.sent_display.read().await.as_ref() == Some(&*front_buffer)
+        };
+
+        let mut out_of_sync_since = self.display_out_of_sync_since.write().await;
+        if in_sync {
+            *out_of_sync_since = None;
+            return false;
+        }
+
+        let now = Utc::now();
+        let since = *out_of_sync_since.get_or_insert(now);
+        if (now - since).to_std().unwrap_or_default() >= max_divergence {
+            *out_of_sync_since = None;
+            return true;
+        }
+        false
+    }
 }
 
 // New ControlPortManager that manages multiple ControlPorts
 pub struct ControlPortManager {
-    pub control_ports: DashMap<String, Arc<ControlPort>>,
+    // Shared with clones (e.g. the web monitor's) so ports added later show up everywhere
+    pub control_ports: Arc<DashMap<String, Arc<ControlPort>>>,
     pub config: Config,
     pub web_monitor: Arc<Mutex<Option<Arc<WebMonitor>>>>,
     shutdown_tx: broadcast::Sender<()>,
@@ -576,7 +1164,7 @@ impl ControlPortManager {
     pub fn new(config: Config) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
         Self {
-            control_ports: DashMap::new(),
+            control_ports: Arc::new(DashMap::new()),
             config,
             web_monitor: Arc::new(Mutex::new(None)),
             shutdown_tx,
@@ -585,22 +1173,60 @@ impl ControlPortManager {
 
     pub async fn initialize(&self) -> Result<()> {
         for (dip, config) in &self.config.controller_addresses {
-            // Use the existing shutdown_tx to create a receiver for this ControlPort
-            let shutdown_rx = self.shutdown_tx.subscribe();
-            let control_port = Arc::new(ControlPort::new(dip.clone(), config.clone(), shutdown_rx));
-
-            if let Err(e) = control_port.start().await {
-                return Err(anyhow!(
-                    "Failed to start control port for DIP {}: {}",
-                    dip,
-                    e
-                ));
-            }
-            self.control_ports.insert(dip.clone(), control_port);
+            self.add_control_port(dip.clone(), config.clone()).await?;
         }
         Ok(())
     }
 
+    // Start a control port for a controller that wasn't in the initial config
+    pub async fn add_control_port(
+        &self,
+        dip: String,
+        config: ControllerConfig,
+    ) -> Result<Arc<ControlPort>> {
+        if self.control_ports.contains_key(&dip) {
+            return Err(anyhow!("Control port for DIP {} already exists", dip));
+        }
+
+        // Use the existing shutdown_tx to create a receiver for this ControlPort
+        let shutdown_rx = self.shutdown_tx.subscribe();
+        let control_port = Arc::new(ControlPort::new(dip.clone(), config, shutdown_rx));
+
+        if let Err(e) = control_port.start().await {
+            return Err(anyhow!(
+                "Failed to start control port for DIP {}: {}",
+                dip,
+                e
+            ));
+        }
+
+        match self.control_ports.entry(dip) {
+            dashmap::mapref::entry::Entry::Occupied(entry) => {
+                // Lost a race with a concurrent add for the same DIP
+                control_port.shutdown().await;
+                Err(anyhow!(
+                    "Control port for DIP {} already exists",
+                    entry.key()
+                ))
+            }
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(control_port.clone());
+                Ok(control_port)
+            }
+        }
+    }
+
+    // Shut down and forget a control port; returns false if the DIP isn't managed
+    pub async fn remove_control_port(&self, dip: &str) -> bool {
+        match self.control_ports.remove(dip) {
+            Some((_, control_port)) => {
+                control_port.shutdown().await;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub async fn start_web_monitor(&self, port: u16) -> Result<()> {
         self.start_web_monitor_with_config(port, 1000).await
     }
@@ -614,6 +1240,8 @@ impl ControlPortManager {
             .await
     }
 
+    // Runs the web monitor until the server stops, so its errors reach the caller; spawn it
+    // to run in the background
     pub async fn start_web_monitor_with_full_config(
         &self,
         port: u16,
@@ -625,31 +1253,54 @@ impl ControlPortManager {
                 .with_log_buffer_size(log_buffer_size)
                 .with_bind_address(bind_address.clone()),
         );
-        let web_monitor_clone = web_monitor.clone();
-
-        // Start web monitor in background task
-        tokio::spawn(async move {
-            if let Err(e) = web_monitor_clone.start_server(port).await {
-                eprintln!("Web monitor error: {}", e);
-            }
-        });
 
         // Use interior mutability to update the web_monitor
-        let mut guard = self.web_monitor.lock().await;
-        *guard = Some(web_monitor);
-        Ok(())
+        {
+            let mut guard = self.web_monitor.lock().await;
+            *guard = Some(web_monitor.clone());
+        }
+
+        web_monitor
+            .start_server(port)
+            .await
+            .map_err(|e| anyhow!("{}", e))
     }
 
     pub fn get_control_port(&self, dip: &str) -> Option<Arc<ControlPort>> {
         self.control_ports.get(dip).map(|cp| cp.clone())
     }
 
-    pub async fn get_all_stats(&self) -> Vec<ControlPortStats> {
-        let mut all_stats = Vec::new();
+    // DIPs of all managed control ports, sorted
+    pub fn list_dips(&self) -> Vec<String> {
+        let mut dips: Vec<String> = self
+            .control_ports
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        dips.sort();
+        dips
+    }
 
-        for control_port in self.control_ports.iter() {
-            let stats = control_port.get_stats().await;
-            all_stats.push(stats);
+    pub async fn get_all_stats(&self) -> Vec<ControlPortStats> {
+        // Collect the ports first so no map guard is held across an await, then fetch
+        // every port's stats concurrently so the total is bounded by the slowest one
+        let control_ports: Vec<Arc<ControlPort>> = self
+            .control_ports
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+
+        let tasks: Vec<_> = control_ports
+            .into_iter()
+            .map(|control_port| tokio::spawn(async move { control_port.get_stats().await }))
+            .collect();
+
+        let mut all_stats = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            match task.await {
+                Ok(stats) => all_stats.push(stats),
+                Err(e) => warn!("Failed to gather control port stats: {}", e),
+            }
         }
 
         all_stats
@@ -691,11 +1342,15 @@ pub struct ControlPort {
     // Communication channels
     pub message_tx: mpsc::UnboundedSender<OutgoingMessage>,
     pub button_broadcast: broadcast::Sender<Vec<bool>>,
+    pub analog_broadcast: broadcast::Sender<Vec<f32>>,
+    pub raw_message_broadcast: broadcast::Sender<serde_json::Value>,
 
     // Internal task handles
     connection_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
     button_forward_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
     shutdown_rx: broadcast::Receiver<()>,
+    // Set by shutdown, for event consumers that should stop waiting
+    shut_down_tx: watch::Sender<bool>,
 
     // Store reference to the underlying ControllerState
     controller_state: Arc<RwLock<Option<Arc<ControllerState>>>>,
@@ -721,10 +1376,18 @@ pub struct ControlPortStats {
     pub bytes_received: u64,
     pub messages_sent: u64,
     pub messages_received: u64,
+    // Messages queued for the controller but not yet written; growth means it has stalled
+    pub pending_messages: usize,
     pub connection_attempts: u64,
+    pub parse_errors: u64,
+    pub disconnect_count: u64,
+    pub current_uptime_seconds: Option<i64>,
+    pub firmware_version: Option<String>,
+    pub capabilities: Option<ControllerCapabilities>,
     pub last_error: Option<String>,
     pub throughput_sent_bps: f64,
     pub throughput_received_bps: f64,
+    pub messages_received_per_sec: f64,
     pub last_throughput_update: Option<DateTime<Utc>>,
     pub last_heartbeat_received: Option<DateTime<Utc>>,
     pub last_noop_sent: Option<DateTime<Utc>>,
@@ -732,6 +1395,39 @@ pub struct ControlPortStats {
     pub noop_sent_active: bool,
 }
 
+impl From<&ControllerStats> for ControlPortStats {
+    fn from(stats: &ControllerStats) -> Self {
+        Self {
+            dip: stats.dip.clone(),
+            ip: stats.ip.clone(),
+            port: stats.port,
+            connected: stats.connected,
+            last_message_time: stats.last_message_time,
+            connection_time: stats.connection_time,
+            bytes_sent: stats.bytes_sent,
+            bytes_received: stats.bytes_received,
+            messages_sent: stats.messages_sent,
+            messages_received: stats.messages_received,
+            pending_messages: stats.pending_messages,
+            connection_attempts: stats.connection_attempts,
+            parse_errors: stats.parse_errors,
+            disconnect_count: stats.disconnect_count,
+            current_uptime_seconds: stats.current_uptime_seconds,
+            firmware_version: stats.firmware_version.clone(),
+            capabilities: stats.capabilities.clone(),
+            last_error: stats.last_error.clone(),
+            throughput_sent_bps: stats.throughput_sent_bps,
+            throughput_received_bps: stats.throughput_received_bps,
+            messages_received_per_sec: stats.messages_received_per_sec,
+            last_throughput_update: stats.last_throughput_update,
+            last_heartbeat_received: stats.last_heartbeat_received,
+            last_noop_sent: stats.last_noop_sent,
+            heartbeat_received_active: stats.heartbeat_received_active,
+            noop_sent_active: stats.noop_sent_active,
+        }
+    }
+}
+
 impl ControlPortStats {
     pub fn heartbeat_received_age_seconds(&self) -> Option<i64> {
         self.last_heartbeat_received.map(|time| {
@@ -755,7 +1451,9 @@ impl ControlPort {
         shutdown_rx: broadcast::Receiver<()>,
     ) -> Self {
         let (message_tx, _message_rx) = mpsc::unbounded_channel();
-        let (button_broadcast, _) = broadcast::channel(100);
+        let (button_broadcast, _) = broadcast::channel(config.button_channel_capacity.max(1));
+        let (analog_broadcast, _) = broadcast::channel(config.button_channel_capacity.max(1));
+        let (raw_message_broadcast, _) = broadcast::channel(100);
 
         let state = Arc::new(RwLock::new(ControlPortState {
             connected: false,
@@ -775,10 +1473,17 @@ impl ControlPort {
             bytes_received: 0,
             messages_sent: 0,
             messages_received: 0,
+            pending_messages: 0,
             connection_attempts: 0,
+            parse_errors: 0,
+            disconnect_count: 0,
+            current_uptime_seconds: None,
+            firmware_version: None,
+            capabilities: None,
             last_error: None,
             throughput_sent_bps: 0.0,
             throughput_received_bps: 0.0,
+            messages_received_per_sec: 0.0,
             last_throughput_update: None,
             last_heartbeat_received: None,
             last_noop_sent: None,
@@ -796,54 +1501,85 @@ impl ControlPort {
             logs,
             message_tx,
             button_broadcast,
+            analog_broadcast,
+            raw_message_broadcast,
             connection_task: Arc::new(RwLock::new(None)),
             button_forward_task: Arc::new(RwLock::new(None)),
             shutdown_rx,
+            shut_down_tx: watch::Sender::new(false),
             controller_state: Arc::new(RwLock::new(None)),
         }
     }
 
+    // Receiver that reads true once this port has shut down
+    pub fn subscribe_shutdown(&self) -> watch::Receiver<bool> {
+        self.shut_down_tx.subscribe()
+    }
+
     pub async fn start(&self) -> Result<()> {
-        // Create a new controller state
-        let controller = Arc::new(ControllerState::new(self.dip.clone(), self.config.clone()));
+        // Create a new controller state that writes straight into this port's log buffer,
+        // so there is a single copy of the log shared by both
+        let mut controller = ControllerState::new(self.dip.clone(), self.config.clone());
+        controller.log = self.logs.clone();
+        let controller = Arc::new(controller);
 
         // Store the controller directly in this ControlPort
 
         *self.controller_state.write().await = Some(controller.clone());
 
+        // Every event from this port's tasks carries its DIP
+        let span = info_span!("control_port", dip = %self.dip);
+
         // Start the button forwarding task to connect ControllerState button events to ControlPort button broadcast
         let controller_clone = controller.clone();
         let button_broadcast_tx = self.button_broadcast.clone();
+        let analog_broadcast_tx = self.analog_broadcast.clone();
+        let raw_message_broadcast_tx = self.raw_message_broadcast.clone();
         let mut shutdown_rx = self.shutdown_rx.resubscribe();
         let button_forward_task = tokio::spawn(async move {
-            // Subscribe to the controller's button broadcast
+            // Subscribe to the controller's button and raw message broadcasts
             let mut button_rx = controller_clone.button_broadcast.subscribe();
+            let mut analog_rx = controller_clone.analog_broadcast.subscribe();
+            let mut raw_message_rx = controller_clone.raw_message_broadcast.subscribe();
 
             loop {
                 tokio::select! {
-                    button_event = button_rx.recv() => {
+                    button_event = recv_button_state(&mut button_rx) => {
                         match button_event {
                             Ok(buttons) => {
                                 // Forward the button event to the ControlPort's button broadcast
                                 if let Err(e) = button_broadcast_tx.send(buttons) {
-                                    println!(
-                                        "[RUST-DEBUG] Failed to forward button event for DIP {}: {:?}",
-                                        controller_clone.dip, e
-                                    );
+                                    debug!("Failed to forward button event: {:?}", e);
                                 }
                             }
-                            Err(broadcast::error::RecvError::Closed) => {
-                                println!(
-                                    "[RUST-DEBUG] Controller button broadcast channel closed for DIP {}, stopping forwarding task",
-                                    controller_clone.dip
-                                );
+                            Err(_) => {
+                                debug!("Controller button broadcast channel closed, stopping forwarding task");
                                 break;
                             }
+                        }
+                    }
+                    analog = analog_rx.recv() => {
+                        match analog {
+                            Ok(values) => {
+                                // No subscribers is fine; analog inputs are opt-in
+                                let _ = analog_broadcast_tx.send(values);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                warn!("Analog forwarding lagged by {} messages, continuing", n);
+                                continue;
+                            }
+                        }
+                    }
+                    raw_message = raw_message_rx.recv() => {
+                        match raw_message {
+                            Ok(json) => {
+                                // No subscribers is fine; raw messages are opt-in
+                                let _ = raw_message_broadcast_tx.send(json);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
                             Err(broadcast::error::RecvError::Lagged(n)) => {
-                                println!(
-                                    "[RUST-DEBUG] Button forwarding task lagged by {} messages for DIP {}, continuing",
-                                    n, controller_clone.dip
-                                );
+                                warn!("Raw message forwarding lagged by {} messages, continuing", n);
                                 continue;
                             }
                         }
@@ -853,7 +1589,7 @@ impl ControlPort {
                     }
                 }
             }
-        });
+        }.instrument(span.clone()));
 
         // Store the button forwarding task handle
         *self.button_forward_task.write().await = Some(button_forward_task);
@@ -861,16 +1597,13 @@ impl ControlPort {
         // Start the controller task
         let controller_clone = controller.clone();
         let shutdown_rx = self.shutdown_rx.resubscribe();
-        let task_handle = tokio::spawn(async move {
-            let _dip = controller_clone.dip.clone();
-
-            // Add panic handler to see if there are any panics
-            std::panic::set_hook(Box::new(|panic_info| {
-                println!("[RUST-DEBUG] PANIC in controller task: {:?}", panic_info);
-            }));
-
-            Self::run_controller_task(controller_clone, shutdown_rx).await;
-        });
+        install_panic_hook();
+        let task_handle = tokio::spawn(
+            async move {
+                Self::run_controller_task(controller_clone, shutdown_rx).await;
+            }
+            .instrument(span),
+        );
 
         // Store the task handle
         *self.connection_task.write().await = Some(task_handle);
@@ -882,16 +1615,28 @@ impl ControlPort {
         controller: Arc<ControllerState>,
         mut shutdown_rx: broadcast::Receiver<()>,
     ) {
-        let mut reconnect_interval = interval(Duration::from_secs(2));
+        let next_reconnect_delay = || {
+            jittered_reconnect_delay(
+                controller.config.reconnect_interval_ms.max(1),
+                controller.config.reconnect_jitter,
+                random_u64(),
+            )
+        };
+        // Re-armed after each check, so the reconnect gap is measured from the end of a slow
+        // attempt and every wait gets fresh jitter
+        let reconnect_timer = tokio::time::sleep(next_reconnect_delay());
+        tokio::pin!(reconnect_timer);
         let mut heartbeat_interval = interval(Duration::from_secs(1));
 
         // Attempt initial connection immediately instead of waiting for first tick
         match Self::attempt_connection(&controller).await {
             Ok(_) => {}
             Err(e) => {
+                warn!("Initial connection failed: {}", e);
                 controller
                     .add_log(
                         LogDirection::Error,
+                        LogKind::Connection,
                         format!("Initial connection failed: {}", e),
                         None,
                     )
@@ -904,21 +1649,26 @@ impl ControlPort {
                 _ = shutdown_rx.recv() => {
                     break;
                 }
-                _ = reconnect_interval.tick() => {
+                _ = &mut reconnect_timer => {
                     let connected = *controller.connected.read().await;
                     if !connected {
                         match Self::attempt_connection(&controller).await {
                             Ok(_) => {
                             }
                             Err(e) => {
+                                warn!("Connection failed: {}", e);
                                 controller.add_log(
                                     LogDirection::Error,
+                                    LogKind::Connection,
                                     format!("Connection failed: {}", e),
                                     None,
                                 ).await;
                             }
                         }
                     }
+                    reconnect_timer
+                        .as_mut()
+                        .reset(tokio::time::Instant::now() + next_reconnect_delay());
                 }
                 _ = heartbeat_interval.tick() => {
                     let connected = *controller.connected.read().await;
@@ -926,10 +1676,32 @@ impl ControlPort {
                         if let Err(e) = controller.send_message(OutgoingMessage::Noop).await {
                             controller.add_log(
                                 LogDirection::Error,
+                                LogKind::Connection,
                                 format!("Heartbeat failed: {}", e),
                                 None,
                             ).await;
                         }
+
+                        // Self-heal a display that never received (or lost) its contents
+                        let resync_secs = controller.config.display_resync_secs;
+                        if resync_secs > 0
+                            && controller.display_needs_resync(Duration::from_secs(resync_secs)).await
+                        {
+                            controller.add_log(
+                                LogDirection::Info,
+                                LogKind::Lcd,
+                                format!("Display out of sync for {}s, resending", resync_secs),
+                                None,
+                            ).await;
+                            if let Err(e) = controller.resync_display().await {
+                                controller.add_log(
+                                    LogDirection::Error,
+                                    LogKind::Lcd,
+                                    format!("Display resync failed: {}", e),
+                                    None,
+                                ).await;
+                            }
+                        }
                     }
                 }
             }
@@ -946,16 +1718,21 @@ impl ControlPort {
 
         let addr = format!("{}:{}", controller.config.ip, controller.config.port);
         let socket_addr: SocketAddr = addr.parse()?;
+        debug!("Attempting connection to {}", addr);
 
         controller
             .add_log(
                 LogDirection::Info,
+                LogKind::Connection,
                 format!("Attempting connection to {}", addr),
                 None,
             )
             .await;
 
-        let stream = timeout(Duration::from_secs(2), TcpStream::connect(socket_addr)).await??;
+        let connect_timeout = Duration::from_millis(controller.config.connect_timeout_ms.max(1));
+        let stream = timeout(connect_timeout, TcpStream::connect(socket_addr))
+            .await
+            .map_err(|_| anyhow!("Connect timed out after {:?}", connect_timeout))??;
 
         // TCP connection success is sufficient validation
 
@@ -966,13 +1743,20 @@ impl ControlPort {
         stats.connection_time = Some(Utc::now());
         drop(stats);
 
+        // The controller's display contents are unknown until we clear it on this connection
+        *controller.sent_display.write().await = None;
+        *controller.display_out_of_sync_since.write().await = None;
+
+        info!("Connected to {}", addr);
         controller
             .add_log(
                 LogDirection::Info,
+                LogKind::Connection,
                 "Connection established and validated, spawning I/O task".to_string(),
                 None,
             )
             .await;
+        controller.log_reconnect().await;
 
         // Recreate the message channel for the new connection
         let (message_tx, message_rx) = mpsc::unbounded_channel();
@@ -980,41 +1764,51 @@ impl ControlPort {
             let mut rx_guard = controller.message_rx.write().await;
             *rx_guard = Some(message_rx);
         }
-        // Update the sender in the controller
+        // Update the sender in the controller; whatever was queued on the old channel is dropped
         {
             let mut tx_guard = controller.message_tx.lock().await;
             *tx_guard = message_tx;
+            controller.pending_messages.store(0, Ordering::Relaxed);
         }
 
         // Spawn the I/O handling task with the established connection
         let controller_clone = controller.clone();
-        tokio::spawn(Self::handle_connection(controller_clone, stream));
+        let io_task = tokio::spawn(
+            Self::handle_connection(controller_clone, stream)
+                .instrument(info_span!("connection", addr = %addr)),
+        );
+        *controller.connection_task.write().await = Some(io_task);
 
         // Resend the current display state after successful connection
         let controller_clone = controller.clone();
-        tokio::spawn(async move {
-            // Give the connection a moment to stabilize
-            tokio::time::sleep(Duration::from_millis(100)).await;
-
-            // Force a complete display refresh to restore the display state
-            if let Err(e) = controller_clone.force_display_refresh().await {
-                controller_clone
-                    .add_log(
-                        LogDirection::Error,
-                        format!("Failed to resend display state after reconnection: {}", e),
-                        None,
-                    )
-                    .await;
-            } else {
-                controller_clone
-                    .add_log(
-                        LogDirection::Info,
-                        "Display state resent after reconnection".to_string(),
-                        None,
-                    )
-                    .await;
+        tokio::spawn(
+            async move {
+                // Give the connection a moment to stabilize
+                tokio::time::sleep(Duration::from_millis(100)).await;
+
+                // Force a complete display refresh to restore the display state
+                if let Err(e) = controller_clone.force_display_refresh().await {
+                    controller_clone
+                        .add_log(
+                            LogDirection::Error,
+                            LogKind::Lcd,
+                            format!("Failed to resend display state after reconnection: {}", e),
+                            None,
+                        )
+                        .await;
+                } else {
+                    controller_clone
+                        .add_log(
+                            LogDirection::Info,
+                            LogKind::Lcd,
+                            "Display state resent after reconnection".to_string(),
+                            None,
+                        )
+                        .await;
+                }
             }
-        });
+            .in_current_span(),
+        );
 
         Ok(())
     }
@@ -1033,6 +1827,7 @@ impl ControlPort {
             controller
                 .add_log(
                     LogDirection::Error,
+                    LogKind::Connection,
                     "Message receiver already taken".to_string(),
                     None,
                 )
@@ -1047,6 +1842,7 @@ impl ControlPort {
         controller
             .add_log(
                 LogDirection::Info,
+                LogKind::Connection,
                 "I/O task started successfully - controller connected".to_string(),
                 None,
             )
@@ -1068,6 +1864,7 @@ impl ControlPort {
                                 if let Err(e) = Self::process_incoming_message(&controller, line.as_bytes()).await {
                                     controller.add_log(
                                         LogDirection::Error,
+                                        LogKind::Other,
                                         format!("Error processing message: {}", e),
                                         Some(line.clone()),
                                     ).await;
@@ -1075,9 +1872,10 @@ impl ControlPort {
                             }
                         }
                         Err(e) => {
-                            println!("[RUST-DEBUG] handle_connection: Read error from DIP {}: {}", controller.dip, e);
+                            warn!("Read error: {}", e);
                             controller.add_log(
                                 LogDirection::Error,
+                                LogKind::Connection,
                                 format!("Read error: {}", e),
                                 None,
                             ).await;
@@ -1087,11 +1885,13 @@ impl ControlPort {
                 }
                 // Handle outgoing messages
                 Some(message) = message_rx.recv() => {
-                    let data = message.to_bytes();
+                    controller.message_dequeued();
+                    let data = message.to_bytes_with_led_encoding(controller.config.led_encoding);
 
                     if let Err(e) = writer.write_all(&data).await {
                         controller.add_log(
                             LogDirection::Error,
+                            LogKind::Connection,
                             format!("Write error: {}", e),
                             None,
                         ).await;
@@ -1100,9 +1900,11 @@ impl ControlPort {
 
                     controller.bytes_sent.fetch_add(data.len() as u64, Ordering::Relaxed);
                     controller.messages_sent.fetch_add(1, Ordering::Relaxed);
+                    controller.record_sent_display(&message).await;
 
                     controller.add_log(
                         LogDirection::Outgoing,
+                        message.log_kind(),
                         format!("Sent: {:?}", message),
                         Some(String::from_utf8_lossy(&data).to_string()),
                     ).await;
@@ -1111,9 +1913,17 @@ impl ControlPort {
         }
 
         // Mark as disconnected
+        info!("Connection closed");
         *controller.connected.write().await = false;
+        controller.disconnect_count.fetch_add(1, Ordering::Relaxed);
+        *controller.disconnected_at.write().await = Some(Utc::now());
         controller
-            .add_log(LogDirection::Info, "Connection closed".to_string(), None)
+            .add_log(
+                LogDirection::Info,
+                LogKind::Connection,
+                "Connection closed".to_string(),
+                None,
+            )
             .await;
     }
 
@@ -1135,6 +1945,15 @@ impl ControlPort {
             Ok(message) => {
                 match message {
                     IncomingMessage::Heartbeat => {
+                        controller
+                            .add_log(
+                                LogDirection::Incoming,
+                                LogKind::Heartbeat,
+                                "Received: Heartbeat".to_string(),
+                                Some(line.clone()),
+                            )
+                            .await;
+
                         // Update heartbeat received tracking
                         *controller.last_heartbeat_received.write().await = Some(Utc::now());
                         *controller.heartbeat_received_active.write().await = true;
@@ -1142,10 +1961,15 @@ impl ControlPort {
                         // Respond with noop
                         controller.send_message(OutgoingMessage::Noop).await?;
                     }
-                    IncomingMessage::Controller { dip } => {
+                    IncomingMessage::Controller {
+                        dip,
+                        version,
+                        capabilities,
+                    } => {
                         controller
                             .add_log(
                                 LogDirection::Incoming,
+                                LogKind::Controller,
                                 format!("Received: Controller identification with DIP: {}", dip),
                                 Some(line.clone()),
                             )
@@ -1155,6 +1979,7 @@ impl ControlPort {
                             controller
                                 .add_log(
                                     LogDirection::Info,
+                                    LogKind::Controller,
                                     format!(
                                         "DIP mismatch: expected {}, got {}",
                                         controller.dip, dip
@@ -1163,33 +1988,61 @@ impl ControlPort {
                                 )
                                 .await;
                         }
+                        if version.is_some() || capabilities.is_some() {
+                            controller
+                                .add_log(
+                                    LogDirection::Info,
+                                    LogKind::Controller,
+                                    format!(
+                                        "Firmware version {}, capabilities {:?}",
+                                        version.as_deref().unwrap_or("unknown"),
+                                        capabilities
+                                    ),
+                                    None,
+                                )
+                                .await;
+                        }
+                        controller.apply_capabilities(version, capabilities).await;
                     }
-                    IncomingMessage::Button { buttons } => {
+                    IncomingMessage::Button { buttons, analog } => {
                         controller
                             .add_log(
                                 LogDirection::Incoming,
+                                LogKind::Button,
                                 format!("Received: Button state {:?}", buttons),
                                 Some(line.clone()),
                             )
                             .await;
                         // Broadcast button state
                         if let Err(e) = controller.button_broadcast.send(buttons) {
-                            println!(
-                                "[RUST-DEBUG] Button broadcast failed for DIP {}: {:?}",
-                                controller.dip, e
-                            )
+                            debug!("Button broadcast failed: {:?}", e)
                         }
+                        if let Some(values) = analog {
+                            // No subscribers is fine; analog inputs are opt-in
+                            let _ = controller.analog_broadcast.send(values);
+                        }
+                    }
+                    IncomingMessage::Raw { json } => {
+                        controller
+                            .add_log(
+                                LogDirection::Incoming,
+                                LogKind::Message,
+                                format!("Received: Unrecognized message {}", json),
+                                Some(line.clone()),
+                            )
+                            .await;
+                        // No subscribers is fine; raw messages are opt-in
+                        let _ = controller.raw_message_broadcast.send(json);
                     }
                 }
             }
             Err(e) => {
-                println!(
-                    "[RUST-DEBUG] Failed to parse message from DIP {}: '{}' -> error: {}",
-                    controller.dip, line, e
-                );
+                controller.parse_errors.fetch_add(1, Ordering::Relaxed);
+                warn!(line = %line, "Failed to parse message: {}", e);
                 controller
                     .add_log(
                         LogDirection::Error,
+                        LogKind::ParseError,
                         format!("Failed to parse message: {}", e),
                         Some(line.clone()),
                     )
@@ -1201,46 +2054,29 @@ impl ControlPort {
     }
 
     pub async fn get_stats(&self) -> ControlPortStats {
-        // Update stats from the underlying controller state before returning
-        if let Some(controller) = self.get_controller_state().await {
-            controller.update_stats().await;
-
-            // Sync the stats from controller to control port
-            let controller_stats = controller.stats.read().await;
-            let mut control_port_stats = self.stats.write().await;
-
-            control_port_stats.connected = controller_stats.connected;
-            control_port_stats.last_message_time = controller_stats.last_message_time;
-            control_port_stats.connection_time = controller_stats.connection_time;
-            control_port_stats.bytes_sent = controller_stats.bytes_sent;
-            control_port_stats.bytes_received = controller_stats.bytes_received;
-            control_port_stats.messages_sent = controller_stats.messages_sent;
-            control_port_stats.messages_received = controller_stats.messages_received;
-            control_port_stats.connection_attempts = controller_stats.connection_attempts;
-            control_port_stats.last_error = controller_stats.last_error.clone();
-            control_port_stats.throughput_sent_bps = controller_stats.throughput_sent_bps;
-            control_port_stats.throughput_received_bps = controller_stats.throughput_received_bps;
-            control_port_stats.last_throughput_update = controller_stats.last_throughput_update;
-            control_port_stats.last_heartbeat_received = controller_stats.last_heartbeat_received;
-            control_port_stats.last_noop_sent = controller_stats.last_noop_sent;
-            control_port_stats.heartbeat_received_active =
-                controller_stats.heartbeat_received_active;
-            control_port_stats.noop_sent_active = controller_stats.noop_sent_active;
-
-            drop(controller_stats);
-            drop(control_port_stats);
-
-            // Also sync the logs
-            self.sync_logs_from_controller(controller).await;
-        }
-
-        // Also update the connection state
-        if let Some(controller) = self.get_controller_state().await {
-            let connected = *controller.connected.read().await;
-            self.update_connection_state(connected).await;
+        // Build the snapshot straight from the controller state; only a stale cache takes a write lock
+        match self.get_controller_state().await {
+            Some(controller) => {
+                controller.refresh_stats_if_stale().await;
+                ControlPortStats::from(&*controller.stats.read().await)
+            }
+            None => self.stats.read().await.clone(),
         }
+    }
 
-        self.stats.read().await.clone()
+    pub async fn get_logs(&self) -> Vec<LogEntry> {
+        self.get_logs_matching(|_| true).await
+    }
+
+    // Copy out only the entries the caller wants instead of the whole buffer
+    pub async fn get_logs_matching(&self, filter: impl Fn(&LogEntry) -> bool) -> Vec<LogEntry> {
+        self.logs
+            .read()
+            .await
+            .iter()
+            .filter(|entry| filter(entry))
+            .cloned()
+            .collect()
     }
 
     pub async fn shutdown(&self) {
@@ -1254,10 +2090,19 @@ impl ControlPort {
             task.abort();
         }
 
+        // Close the live connection, if any
+        if let Some(controller) = self.get_controller_state().await {
+            if let Some(task) = controller.connection_task.write().await.take() {
+                task.abort();
+            }
+            *controller.connected.write().await = false;
+        }
+
         // Update state
         let mut state = self.state.write().await;
         state.connected = false;
         state.last_error = Some("Shutdown".to_string());
+        self.shut_down_tx.send_replace(true);
     }
 
     // Delegate methods to the underlying ControllerState
@@ -1273,13 +2118,32 @@ impl ControlPort {
         }
     }
 
+    pub async fn get_display_snapshot(&self) -> Vec<String> {
+        match self.get_controller_state().await {
+            Some(controller) => controller.get_display_snapshot().await,
+            None => Vec::new(),
+        }
+    }
+
+    pub async fn clear_line(&self, y: u16) {
+        if let Some(controller) = self.get_controller_state().await {
+            controller.clear_line(y).await;
+        }
+    }
+
+    pub async fn write_display_wrap(&self, x: u16, y: u16, text: &str) {
+        if let Some(controller) = self.get_controller_state().await {
+            controller.write_display_wrap(x, y, text).await;
+        }
+    }
+
     pub async fn commit_display(&self) -> Result<(), String> {
         if let Some(controller) = self.get_controller_state().await {
             match controller.commit_display().await {
                 Ok(messages) => {
                     for message in messages {
                         if let Err(e) = self.send_message(message).await {
-                            println!("[RUST-DEBUG] ControlPort::commit_display: Failed to send message for DIP {}: {}", self.dip, e);
+                            warn!(dip = %self.dip, "Failed to send display message: {}", e);
                         }
                     }
                     Ok(())
@@ -1298,9 +2162,13 @@ impl ControlPort {
 
     pub async fn set_leds(&self, rgb_values: Vec<(u8, u8, u8)>) {
         if let Some(controller) = self.get_controller_state().await {
-            let _ = controller
-                .send_message(OutgoingMessage::Led { rgb_values })
-                .await;
+            let _ = controller.set_leds(rgb_values).await;
+        }
+    }
+
+    pub async fn fade_leds(&self, target: Vec<(u8, u8, u8)>, duration_ms: u32) {
+        if let Some(controller) = self.get_controller_state().await {
+            controller.fade_leds(target, duration_ms).await;
         }
     }
 
@@ -1333,19 +2201,6 @@ impl ControlPort {
             state.last_error = None;
         }
     }
-
-    // Sync logs from the controller state to the control port logs
-    async fn sync_logs_from_controller(&self, controller: Arc<ControllerState>) {
-        let controller_logs = controller.log.read().await;
-        let mut control_port_logs = self.logs.write().await;
-
-        // Clear existing logs and copy from controller
-        control_port_logs.clear();
-        control_port_logs.extend(controller_logs.iter().cloned());
-
-        drop(controller_logs);
-        drop(control_port_logs);
-    }
 }
 
 #[cfg(test)]
@@ -1357,6 +2212,12 @@ mod tests {
         let config = ControllerConfig {
             ip: "127.0.0.1".to_string(),
             port: 1234,
+            button_channel_capacity: DEFAULT_BUTTON_CHANNEL_CAPACITY,
+            display_resync_secs: DEFAULT_DISPLAY_RESYNC_SECS,
+            led_encoding: LedEncoding::Base64,
+            connect_timeout_ms: DEFAULT_CONNECT_TIMEOUT_MS,
+            reconnect_interval_ms: DEFAULT_RECONNECT_INTERVAL_MS,
+            reconnect_jitter: DEFAULT_RECONNECT_JITTER,
         };
         ControllerState::new("test_dip".to_string(), config)
     }
@@ -1595,4 +2456,711 @@ mod tests {
         let noop_msg = OutgoingMessage::Noop;
         assert_eq!(noop_msg.to_bytes(), Bytes::from("noop\n"));
     }
+
+    #[test]
+    fn test_led_message_encodings() {
+        let led_msg = OutgoingMessage::Led {
+            rgb_values: vec![(255, 0, 10), (1, 2, 3)],
+        };
+        let payload = [2u8, 0, 255, 0, 10, 1, 2, 3];
+
+        // base64 of the payload, the default
+        assert_eq!(led_msg.to_bytes(), Bytes::from("led:AgD/AAoBAgM=\n"));
+        assert_eq!(
+            led_msg.to_bytes_with_led_encoding(LedEncoding::Base64),
+            led_msg.to_bytes()
+        );
+
+        let mut raw = b"ledraw:".to_vec();
+        raw.extend_from_slice(&payload);
+        raw.push(b'\n');
+        assert_eq!(
+            led_msg.to_bytes_with_led_encoding(LedEncoding::Raw),
+            Bytes::from(raw)
+        );
+
+        // Non-LED messages are unaffected by the LED encoding
+        assert_eq!(
+            OutgoingMessage::Noop.to_bytes_with_led_encoding(LedEncoding::Raw),
+            Bytes::from("noop\n")
+        );
+
+        let config: ControllerConfig =
+            serde_json::from_str(r#"{"ip": "10.0.0.1", "port": 51333, "led_encoding": "raw"}"#)
+                .unwrap();
+        assert_eq!(config.led_encoding, LedEncoding::Raw);
+        assert_eq!(config.connect_timeout_ms, DEFAULT_CONNECT_TIMEOUT_MS);
+        assert_eq!(config.reconnect_interval_ms, DEFAULT_RECONNECT_INTERVAL_MS);
+        assert_eq!(config.reconnect_jitter, DEFAULT_RECONNECT_JITTER);
+    }
+
+    #[test]
+    fn test_jittered_reconnect_delay_stays_within_bounds() {
+        assert_eq!(
+            jittered_reconnect_delay(2000, 0.25, 0),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            jittered_reconnect_delay(2000, 0.25, u64::MAX),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            jittered_reconnect_delay(2000, 0.0, 12345),
+            Duration::from_millis(2000)
+        );
+        // Out-of-range jitter is clamped so the delay never reaches zero or goes negative
+        assert_eq!(
+            jittered_reconnect_delay(2000, 5.0, 0),
+            Duration::from_millis(1)
+        );
+        assert_eq!(
+            jittered_reconnect_delay(2000, f64::NAN, 0),
+            Duration::from_millis(2000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_snapshot_is_cached_within_ttl() {
+        let controller = create_test_controller_state();
+
+        controller.refresh_stats_if_stale().await;
+        controller.bytes_sent.fetch_add(42, Ordering::Relaxed);
+
+        // A second poll right away is served from the cached snapshot
+        controller.refresh_stats_if_stale().await;
+        assert_eq!(controller.stats.read().await.bytes_sent, 0);
+
+        // Once the snapshot has aged past the TTL it is recomputed
+        tokio::time::sleep(Duration::from_millis(STATS_SNAPSHOT_TTL_MS as u64 + 20)).await;
+        controller.refresh_stats_if_stale().await;
+        assert_eq!(controller.stats.read().await.bytes_sent, 42);
+    }
+
+    #[tokio::test]
+    async fn test_control_port_shares_log_with_controller() {
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let control_port = ControlPort::new(
+            "test_dip".to_string(),
+            create_test_controller_state().config,
+            shutdown_rx,
+        );
+        control_port.start().await.unwrap();
+
+        let controller = control_port.get_controller_state().await.unwrap();
+        assert!(Arc::ptr_eq(&controller.log, &control_port.logs));
+
+        controller
+            .add_log(LogDirection::Info, LogKind::Other, "one".to_string(), None)
+            .await;
+        controller
+            .add_log(LogDirection::Error, LogKind::Other, "two".to_string(), None)
+            .await;
+        let errors = control_port
+            .get_logs_matching(|entry| entry.direction == LogDirection::Error)
+            .await;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "two");
+
+        let shut_down = control_port.subscribe_shutdown();
+        assert!(!*shut_down.borrow());
+        control_port.shutdown().await;
+        assert!(*shut_down.borrow());
+    }
+
+    #[tokio::test]
+    async fn test_control_port_stats_read_from_controller() {
+        let config = ControllerConfig {
+            ip: "127.0.0.1".to_string(),
+            port: 1234,
+            button_channel_capacity: DEFAULT_BUTTON_CHANNEL_CAPACITY,
+            display_resync_secs: DEFAULT_DISPLAY_RESYNC_SECS,
+            led_encoding: LedEncoding::Base64,
+            connect_timeout_ms: DEFAULT_CONNECT_TIMEOUT_MS,
+            reconnect_interval_ms: DEFAULT_RECONNECT_INTERVAL_MS,
+            reconnect_jitter: DEFAULT_RECONNECT_JITTER,
+        };
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let control_port = ControlPort::new("test_dip".to_string(), config.clone(), shutdown_rx);
+
+        let controller = Arc::new(ControllerState::new("test_dip".to_string(), config));
+        controller.messages_received.fetch_add(3, Ordering::Relaxed);
+        *control_port.controller_state.write().await = Some(controller);
+
+        let stats = control_port.get_stats().await;
+        assert_eq!(stats.dip, "test_dip");
+        assert_eq!(stats.messages_received, 3);
+    }
+
+    #[test]
+    fn test_unrecognized_message_parses_as_raw() {
+        let message = IncomingMessage::from_json(r#"{"type":"encoder","delta":-3}"#).unwrap();
+        match message {
+            IncomingMessage::Raw { json } => {
+                assert_eq!(json["type"], "encoder");
+                assert_eq!(json["delta"], -3);
+            }
+            _ => panic!("Expected Raw message, got {:?}", message),
+        }
+
+        // Malformed JSON and non-object payloads are still errors
+        assert!(IncomingMessage::from_json("{not json").is_err());
+        assert!(IncomingMessage::from_json("42").is_err());
+    }
+
+    #[test]
+    fn test_malformed_builtin_messages_are_errors() {
+        for malformed in [
+            r#"{"buttons":"garbage"}"#,
+            r#"{"buttons":[true,"x"]}"#,
+            r#"{"type":"controller"}"#,
+            r#"{"type":"controller","dip":3}"#,
+            r#"{"type":7}"#,
+        ] {
+            assert!(
+                IncomingMessage::from_json(malformed).is_err(),
+                "{} should not parse",
+                malformed
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_errors_are_counted() {
+        let controller = Arc::new(create_test_controller_state());
+
+        ControlPort::process_incoming_message(&controller, b"not json\n")
+            .await
+            .unwrap();
+        ControlPort::process_incoming_message(&controller, b"{\"type\":\"heartbeat\"}\n")
+            .await
+            .unwrap();
+        ControlPort::process_incoming_message(&controller, b"{broken\n")
+            .await
+            .unwrap();
+        ControlPort::process_incoming_message(&controller, b"{\"type\":\"controller\"}\n")
+            .await
+            .unwrap();
+
+        controller.update_stats().await;
+        let stats = controller.stats.read().await;
+        assert_eq!(stats.parse_errors, 3);
+        assert_eq!(stats.messages_received, 4);
+    }
+
+    #[test]
+    fn test_interpolate_leds() {
+        let from = vec![(0, 0, 0), (200, 100, 50)];
+        let to = vec![(100, 200, 255), (0, 100, 150), (60, 60, 60)];
+
+        assert_eq!(
+            interpolate_leds(&from, &to, 0.0),
+            vec![(0, 0, 0), (200, 100, 50), (0, 0, 0)]
+        );
+        assert_eq!(
+            interpolate_leds(&from, &to, 0.5),
+            vec![(50, 100, 128), (100, 100, 100), (30, 30, 30)]
+        );
+        assert_eq!(interpolate_leds(&from, &to, 1.0), to);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fade_leds_ends_at_target() {
+        let controller = Arc::new(create_test_controller_state());
+        let mut message_rx = controller.message_rx.write().await.take().unwrap();
+
+        controller.set_leds(vec![(0, 0, 0)]).await.unwrap();
+        let start = tokio::time::Instant::now();
+        controller.fade_leds(vec![(90, 90, 90)], 99).await;
+
+        let mut frames = Vec::new();
+        let mut sent_at = Vec::new();
+        while frames.len() < 4 {
+            if let Some(OutgoingMessage::Led { rgb_values }) = message_rx.recv().await {
+                frames.push(rgb_values);
+                sent_at.push(start.elapsed());
+            }
+        }
+        // Fade frames are one LED_FADE_FRAME_MS apart, the last at the full duration
+        assert_eq!(sent_at, [0, 33, 66, 99].map(Duration::from_millis).to_vec());
+
+        // The initial set plus three fade frames, one per LED_FADE_FRAME_MS
+        assert_eq!(
+            frames,
+            vec![
+                vec![(0, 0, 0)],
+                vec![(30, 30, 30)],
+                vec![(60, 60, 60)],
+                vec![(90, 90, 90)]
+            ]
+        );
+        assert_eq!(*controller.led_buffer.read().await, vec![(90, 90, 90)]);
+    }
+
+    #[tokio::test]
+    async fn test_uptime_and_disconnect_count() {
+        let controller = create_test_controller_state();
+
+        controller.stats.write().await.connection_time =
+            Some(Utc::now() - chrono::Duration::seconds(5));
+        *controller.connected.write().await = true;
+        controller.update_stats().await;
+        assert!(controller.stats.read().await.current_uptime_seconds >= Some(5));
+
+        *controller.connected.write().await = false;
+        controller.disconnect_count.fetch_add(1, Ordering::Relaxed);
+        controller.update_stats().await;
+        let stats = controller.stats.read().await;
+        assert_eq!(stats.current_uptime_seconds, None);
+        assert_eq!(stats.disconnect_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_incoming_message_rate() {
+        let controller = create_test_controller_state();
+        controller.update_stats().await;
+        assert_eq!(controller.stats.read().await.messages_received_per_sec, 0.0);
+
+        // Ten messages over the last second, smoothed with the 2s time constant
+        controller.stats.write().await.last_throughput_update =
+            Some(Utc::now() - chrono::Duration::seconds(1));
+        controller
+            .messages_received
+            .fetch_add(10, Ordering::Relaxed);
+        controller.update_stats().await;
+        let rate = controller.stats.read().await.messages_received_per_sec;
+        assert!(rate > 3.0 && rate < 4.0, "rate was {}", rate);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_logs_downtime() {
+        let controller = create_test_controller_state();
+
+        // First connection has no prior disconnect to report
+        controller.log_reconnect().await;
+        assert!(controller.log.read().await.is_empty());
+
+        *controller.disconnected_at.write().await =
+            Some(Utc::now() - chrono::Duration::seconds(14));
+        controller.log_reconnect().await;
+        let log = controller.log.read().await;
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].direction, LogDirection::Info);
+        assert_eq!(log[0].message, "Reconnected after 14s down");
+        drop(log);
+        assert!(controller.disconnected_at.read().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_controller_capabilities_resize_buffers() {
+        let controller = Arc::new(create_test_controller_state());
+
+        // Legacy identification without version or capabilities
+        match IncomingMessage::from_json(r#"{"type":"controller","dip":"3"}"#).unwrap() {
+            IncomingMessage::Controller {
+                version,
+                capabilities,
+                ..
+            } => {
+                assert_eq!(version, None);
+                assert_eq!(capabilities, None);
+            }
+            message => panic!("Expected Controller message, got {:?}", message),
+        }
+
+        controller.write_display(0, 0, "Hello").await;
+        ControlPort::process_incoming_message(
+            &controller,
+            br#"{"type":"controller","dip":"test_dip","version":"2.1","capabilities":{"lcd_width":16,"lcd_height":2,"led_count":8}}
+"#,
+        )
+        .await
+        .unwrap();
+
+        let back_buffer = controller.back_buffer.read().await;
+        assert_eq!(back_buffer.len(), 2);
+        assert!(back_buffer.iter().all(|row| row.len() == 16));
+        assert_eq!(back_buffer[0][..5].iter().collect::<String>(), "Hello");
+        drop(back_buffer);
+        assert_eq!(controller.led_buffer.read().await.len(), 8);
+
+        // Writes are clipped to the new geometry
+        controller.write_display(14, 1, "abcd").await;
+        controller.write_display(0, 3, "gone").await;
+        assert_eq!(controller.back_buffer.read().await[1][14..], ['a', 'b']);
+
+        controller.update_stats().await;
+        let stats = controller.stats.read().await;
+        assert_eq!(stats.firmware_version.as_deref(), Some("2.1"));
+        assert_eq!(
+            stats.capabilities,
+            Some(ControllerCapabilities {
+                lcd_width: Some(16),
+                lcd_height: Some(2),
+                led_count: Some(8),
+            })
+        );
+        drop(stats);
+
+        // Absurd reported sizes are capped instead of allocated
+        controller
+            .apply_capabilities(
+                None,
+                Some(ControllerCapabilities {
+                    lcd_width: Some(u16::MAX),
+                    lcd_height: Some(u16::MAX),
+                    led_count: Some(usize::MAX),
+                }),
+            )
+            .await;
+        let back_buffer = controller.back_buffer.read().await;
+        assert_eq!(back_buffer.len(), MAX_LCD_HEIGHT as usize);
+        assert!(back_buffer
+            .iter()
+            .all(|row| row.len() == MAX_LCD_WIDTH as usize));
+        drop(back_buffer);
+        assert_eq!(controller.led_buffer.read().await.len(), MAX_LED_COUNT);
+    }
+
+    #[tokio::test]
+    async fn test_lagged_button_receiver_resyncs_to_latest_state() {
+        let (tx, mut rx) = broadcast::channel(4);
+
+        // Overflow the channel; the final state is a release of every button
+        for i in 0..10 {
+            tx.send(vec![i % 2 == 0, true]).unwrap();
+        }
+        tx.send(vec![false, false]).unwrap();
+
+        assert_eq!(
+            recv_button_state(&mut rx).await.unwrap(),
+            vec![false, false]
+        );
+        assert!(rx.try_recv().is_err());
+
+        // Without a lag, states are delivered in order
+        tx.send(vec![true, false]).unwrap();
+        tx.send(vec![false, true]).unwrap();
+        assert_eq!(recv_button_state(&mut rx).await.unwrap(), vec![true, false]);
+        assert_eq!(recv_button_state(&mut rx).await.unwrap(), vec![false, true]);
+
+        drop(tx);
+        assert!(recv_button_state(&mut rx).await.is_err());
+    }
+
+    #[test]
+    fn test_button_channel_capacity_config() {
+        let config: Config =
+            serde_json::from_str(r#"{"controller_addresses": {"1": {"ip": "10.0.0.1", "port": 51333}, "2": {"ip": "10.0.0.2", "port": 51333, "button_channel_capacity": 1024}}}"#)
+                .unwrap();
+        assert_eq!(
+            config.controller_addresses["1"].button_channel_capacity,
+            DEFAULT_BUTTON_CHANNEL_CAPACITY
+        );
+        assert_eq!(
+            config.controller_addresses["2"].button_channel_capacity,
+            1024
+        );
+
+        // A chatty controller can queue more than the default without lagging
+        let controller =
+            ControllerState::new("2".to_string(), config.controller_addresses["2"].clone());
+        let mut rx = controller.button_broadcast.subscribe();
+        for _ in 0..500 {
+            controller.button_broadcast.send(vec![true]).unwrap();
+        }
+        assert_eq!(rx.len(), 500);
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_display_resync_after_divergence() {
+        let controller = create_test_controller_state();
+        controller.write_display(0, 0, "Hello").await;
+        let messages = controller.commit_display().await.unwrap();
+
+        // Nothing has been written to the socket yet, so the display is out of sync
+        assert!(
+            !controller
+                .display_needs_resync(Duration::from_secs(60))
+                .await
+        );
+        assert!(controller.display_needs_resync(Duration::ZERO).await);
+
+        // Once the clear and the committed writes go out, the display is in sync
+        controller
+            .record_sent_display(&OutgoingMessage::LcdClear)
+            .await;
+        for message in &messages {
+            controller.record_sent_display(message).await;
+        }
+        assert!(!controller.display_needs_resync(Duration::ZERO).await);
+        assert!(controller.display_out_of_sync_since.read().await.is_none());
+
+        // A resync resends the committed contents from a cleared display
+        let mut message_rx = controller.message_rx.write().await.take().unwrap();
+        controller.write_display(0, 1, "uncommitted").await;
+        controller.resync_display().await.unwrap();
+        assert!(matches!(
+            message_rx.recv().await,
+            Some(OutgoingMessage::LcdClear)
+        ));
+        match message_rx.recv().await {
+            Some(OutgoingMessage::LcdWrite { x, y, text }) => {
+                assert_eq!((x, y, text.as_str()), (0, 0, "Hello"));
+            }
+            other => panic!("Expected LcdWrite, got {:?}", other),
+        }
+        assert!(message_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pending_messages_tracks_queue_depth() {
+        let controller = create_test_controller_state();
+        let mut message_rx = controller.message_rx.write().await.take().unwrap();
+
+        controller
+            .send_message(OutgoingMessage::Noop)
+            .await
+            .unwrap();
+        controller
+            .send_message(OutgoingMessage::LcdClear)
+            .await
+            .unwrap();
+        controller.update_stats().await;
+        assert_eq!(controller.stats.read().await.pending_messages, 2);
+
+        message_rx.recv().await.unwrap();
+        controller.message_dequeued();
+        controller.update_stats().await;
+        assert_eq!(controller.stats.read().await.pending_messages, 1);
+
+        // A failed send isn't left counted
+        drop(message_rx);
+        assert!(controller
+            .send_message(OutgoingMessage::Noop)
+            .await
+            .is_err());
+        assert_eq!(controller.pending_messages.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_log_entries_record_kind() {
+        let controller = Arc::new(create_test_controller_state());
+
+        for line in [
+            &b"{\"type\":\"heartbeat\"}\n"[..],
+            b"{\"buttons\":[1,0]}\n",
+            b"{\"type\":\"controller\",\"dip\":\"test_dip\"}\n",
+            b"{\"type\":\"encoder\"}\n",
+            b"not json\n",
+        ] {
+            ControlPort::process_incoming_message(&controller, line)
+                .await
+                .unwrap();
+        }
+
+        let kinds: Vec<LogKind> = controller
+            .log
+            .read()
+            .await
+            .iter()
+            .map(|log| log.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LogKind::Heartbeat,
+                LogKind::Button,
+                LogKind::Controller,
+                LogKind::Message,
+                LogKind::ParseError
+            ]
+        );
+        assert_eq!(OutgoingMessage::Noop.log_kind(), LogKind::Noop);
+        assert_eq!(OutgoingMessage::LcdClear.log_kind(), LogKind::Lcd);
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("hello big world", 8, 10),
+            vec!["hello", "big world"]
+        );
+        // Long words are hard-wrapped, newlines force a break
+        assert_eq!(
+            wrap_text("abcdefghijkl xy\nz", 5, 5),
+            vec!["abcde", "fghij", "kl xy", "z"]
+        );
+        assert_eq!(wrap_text("abcdef gh", 3, 10), vec!["", "abcdef gh"]);
+        assert_eq!(wrap_text("", 4, 4), vec![""]);
+    }
+
+    #[tokio::test]
+    async fn test_write_display_wrap() {
+        let controller = create_test_controller_state();
+        controller
+            .write_display_wrap(12, 1, "Status: all controllers online and synchronized")
+            .await;
+
+        let rows: Vec<String> = controller
+            .back_buffer
+            .read()
+            .await
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                " ".repeat(20),
+                format!("{}Status: ", " ".repeat(12)),
+                format!("{:<20}", "all controllers"),
+                format!("{:<20}", "online and"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear_line_sends_minimal_update() {
+        let controller = create_test_controller_state();
+        controller.write_display(0, 0, "MAIN MENU").await;
+        controller.write_display(0, 1, "12:00:00").await;
+        controller.commit_display().await.unwrap();
+
+        controller.clear_line(1).await;
+        controller.clear_line(9).await; // Out of range is ignored
+        let messages = controller.commit_display().await.unwrap();
+
+        assert_eq!(messages.len(), 1);
+        match &messages[0] {
+            OutgoingMessage::LcdWrite { x, y, text } => {
+                assert_eq!((*x, *y, text.as_str()), (0, 1, "        "));
+            }
+            other => panic!("Expected LcdWrite, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_display_snapshot_reflects_committed_contents() {
+        let controller = create_test_controller_state();
+        controller.write_display(0, 0, "MAIN MENU").await;
+        assert_eq!(controller.get_display_snapshot().await[0].trim_end(), "");
+
+        controller.commit_display().await.unwrap();
+        let snapshot = controller.get_display_snapshot().await;
+        assert_eq!(snapshot.len(), 4);
+        assert_eq!(snapshot[0].trim_end(), "MAIN MENU");
+        assert_eq!(snapshot[1], " ".repeat(20));
+    }
+
+    #[tokio::test]
+    async fn test_button_message_with_analog_values() {
+        match IncomingMessage::from_json(r#"{"buttons":[1,0],"analog":[0.25,-1.0]}"#).unwrap() {
+            IncomingMessage::Button { buttons, analog } => {
+                assert_eq!(buttons, vec![true, false]);
+                assert_eq!(analog, Some(vec![0.25, -1.0]));
+            }
+            message => panic!("Expected Button message, got {:?}", message),
+        }
+
+        // Buttons alone still parse, with no analog values
+        match IncomingMessage::from_json(r#"{"buttons":[true]}"#).unwrap() {
+            IncomingMessage::Button { buttons, analog } => {
+                assert_eq!(buttons, vec![true]);
+                assert_eq!(analog, None);
+            }
+            message => panic!("Expected Button message, got {:?}", message),
+        }
+
+        let controller = Arc::new(create_test_controller_state());
+        let mut button_rx = controller.button_broadcast.subscribe();
+        let mut analog_rx = controller.analog_broadcast.subscribe();
+        ControlPort::process_incoming_message(&controller, b"{\"buttons\":[1],\"analog\":[0.5]}\n")
+            .await
+            .unwrap();
+        ControlPort::process_incoming_message(&controller, b"{\"buttons\":[0]}\n")
+            .await
+            .unwrap();
+        assert_eq!(button_rx.recv().await.unwrap(), vec![true]);
+        assert_eq!(button_rx.recv().await.unwrap(), vec![false]);
+        assert_eq!(analog_rx.recv().await.unwrap(), vec![0.5]);
+        assert!(analog_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_manager_lists_dips() {
+        let manager = ControlPortManager::new(Config {
+            controller_addresses: std::collections::HashMap::new(),
+        });
+        assert!(manager.list_dips().is_empty());
+
+        for dip in ["2", "10", "1"] {
+            let control_port = ControlPort::new(
+                dip.to_string(),
+                create_test_controller_state().config,
+                manager.shutdown_tx.subscribe(),
+            );
+            manager
+                .control_ports
+                .insert(dip.to_string(), Arc::new(control_port));
+        }
+        assert_eq!(manager.list_dips(), vec!["1", "10", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_manager_gathers_stats_from_every_port() {
+        let manager = ControlPortManager::new(Config {
+            controller_addresses: std::collections::HashMap::new(),
+        });
+        for dip in ["1", "2", "3"] {
+            let control_port = ControlPort::new(
+                dip.to_string(),
+                create_test_controller_state().config,
+                manager.shutdown_tx.subscribe(),
+            );
+            manager
+                .control_ports
+                .insert(dip.to_string(), Arc::new(control_port));
+        }
+
+        let mut dips: Vec<String> = manager
+            .get_all_stats()
+            .await
+            .into_iter()
+            .map(|stats| stats.dip)
+            .collect();
+        dips.sort();
+        assert_eq!(dips, vec!["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_manager_add_and_remove_control_ports() {
+        let manager = ControlPortManager::new(Config {
+            controller_addresses: std::collections::HashMap::new(),
+        });
+        let config = create_test_controller_state().config;
+
+        manager
+            .add_control_port("5".to_string(), config.clone())
+            .await
+            .unwrap();
+        assert!(manager
+            .add_control_port("5".to_string(), config.clone())
+            .await
+            .is_err());
+
+        // Clones (like the web monitor's) see ports added after they were made
+        let monitor_view = manager.clone();
+        manager
+            .add_control_port("6".to_string(), config)
+            .await
+            .unwrap();
+        assert_eq!(monitor_view.list_dips(), vec!["5", "6"]);
+
+        assert!(manager.remove_control_port("5").await);
+        assert!(!manager.remove_control_port("5").await);
+        assert_eq!(manager.list_dips(), vec!["6"]);
+
+        manager.shutdown().await;
+    }
 }
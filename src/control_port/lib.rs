@@ -2,7 +2,9 @@ use anyhow::Result;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::net::TcpStream;
 use tokio::runtime::Runtime;
@@ -11,15 +13,48 @@ use tokio::time::{interval, timeout};
 
 // Re-export the control_port module
 pub mod control_port;
+pub mod python_logging;
 pub mod web_monitor;
 
-use control_port::{Config, ControlPort, ControlPortManager};
+use control_port::{recv_button_state, Config, ControlPort, ControlPortManager, ControllerConfig};
 use web_monitor::WebMonitor;
 
+// Report a failure in a background task through Python's logging module (logger
+// "control_port_rs"), so the application can observe it with its own handlers; falls back to
+// stderr if logging can't be reached
+fn log_background_error(message: &str) {
+    let logged = Python::with_gil(|py| -> PyResult<()> {
+        py.import("logging")?
+            .call_method1("getLogger", ("control_port_rs",))?
+            .call_method1("error", (message,))?;
+        Ok(())
+    });
+    if logged.is_err() {
+        eprintln!("{}", message);
+    }
+}
+
 #[pymodule]
 mod control_port_rs {
     use super::*;
 
+    // Forward this module's tracing events (connection attempts, read and parse errors, ...) at
+    // `level` and above to Python's logging module, under loggers named after their Rust module
+    // ("control_port_rs.control_port"). Returns False if logging was already set up.
+    #[pyfunction]
+    #[pyo3(signature = (level="info"))]
+    fn init_logging(level: &str) -> PyResult<bool> {
+        let level = level
+            .parse::<tracing::level_filters::LevelFilter>()
+            .map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid log level '{}': expected trace, debug, info, warn, error or off",
+                    level
+                ))
+            })?;
+        Ok(python_logging::init(level))
+    }
+
     #[pyclass(name = "ControlPortManager")]
     struct ControlPortManagerPy {
         runtime: Runtime,
@@ -57,7 +92,7 @@ mod control_port_rs {
             let manager = self.manager.clone();
             self.runtime.spawn(async move {
                 if let Err(e) = manager.start_web_monitor(port).await {
-                    eprintln!("Web monitor error: {}", e);
+                    log_background_error(&format!("Web monitor error: {}", e));
                 }
             });
             Ok(())
@@ -74,7 +109,7 @@ mod control_port_rs {
                     .start_web_monitor_with_config(port, log_buffer_size)
                     .await
                 {
-                    eprintln!("Web monitor error: {}", e);
+                    log_background_error(&format!("Web monitor error: {}", e));
                 }
             });
             Ok(())
@@ -92,7 +127,7 @@ mod control_port_rs {
                     .start_web_monitor_with_full_config(port, log_buffer_size, bind_address)
                     .await
                 {
-                    eprintln!("Web monitor error: {}", e);
+                    log_background_error(&format!("Web monitor error: {}", e));
                 }
             });
             Ok(())
@@ -107,6 +142,49 @@ mod control_port_rs {
                 })
         }
 
+        // config_json takes the same fields as an entry of controller_addresses
+        fn add_control_port(&self, dip: String, config_json: &str) -> PyResult<ControlPortPy> {
+            let config: ControllerConfig = serde_json::from_str(config_json)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+            let control_port = self
+                .runtime
+                .block_on(async { self.manager.add_control_port(dip, config).await })
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+            Ok(ControlPortPy {
+                runtime_handle: self.runtime.handle().clone(),
+                control_port,
+            })
+        }
+
+        fn remove_control_port(&self, dip: &str) -> bool {
+            self.runtime
+                .block_on(async { self.manager.remove_control_port(dip).await })
+        }
+
+        fn list_dips(&self) -> Vec<String> {
+            self.manager.list_dips()
+        }
+
+        // DIP, ip and port of every managed control port
+        fn list_control_ports(&self) -> PyResult<Vec<PyObject>> {
+            Python::with_gil(|py| {
+                self.manager
+                    .list_dips()
+                    .into_iter()
+                    .filter_map(|dip| self.manager.get_control_port(&dip))
+                    .map(|control_port| {
+                        let dict = PyDict::new(py);
+                        dict.set_item("dip", control_port.dip.clone())?;
+                        dict.set_item("ip", control_port.config.ip.clone())?;
+                        dict.set_item("port", control_port.config.port)?;
+                        Ok(dict.into())
+                    })
+                    .collect()
+            })
+        }
+
         fn get_all_stats(&self) -> PyResult<Vec<PyObject>> {
             let stats = self
                 .runtime
@@ -134,10 +212,26 @@ mod control_port_rs {
                         dict.set_item("bytes_received", stat.bytes_received)?;
                         dict.set_item("messages_sent", stat.messages_sent)?;
                         dict.set_item("messages_received", stat.messages_received)?;
+                        dict.set_item("pending_messages", stat.pending_messages)?;
                         dict.set_item("connection_attempts", stat.connection_attempts)?;
+                        dict.set_item("parse_errors", stat.parse_errors)?;
+                        dict.set_item("disconnect_count", stat.disconnect_count)?;
+                        dict.set_item("current_uptime_seconds", stat.current_uptime_seconds)?;
+                        dict.set_item("firmware_version", stat.firmware_version.as_deref())?;
+                        match &stat.capabilities {
+                            Some(caps) => {
+                                let caps_dict = PyDict::new(py);
+                                caps_dict.set_item("lcd_width", caps.lcd_width)?;
+                                caps_dict.set_item("lcd_height", caps.lcd_height)?;
+                                caps_dict.set_item("led_count", caps.led_count)?;
+                                dict.set_item("capabilities", caps_dict)?;
+                            }
+                            None => dict.set_item("capabilities", py.None())?,
+                        }
                         dict.set_item("last_error", stat.last_error.as_deref())?;
                         dict.set_item("throughput_sent_bps", stat.throughput_sent_bps)?;
                         dict.set_item("throughput_received_bps", stat.throughput_received_bps)?;
+                        dict.set_item("messages_received_per_sec", stat.messages_received_per_sec)?;
                         dict.set_item(
                             "last_throughput_update",
                             stat.last_throughput_update.map(|dt| dt.to_rfc3339()),
@@ -179,6 +273,25 @@ mod control_port_rs {
             Ok(())
         }
 
+        fn get_display_snapshot(&self) -> Vec<String> {
+            self.runtime_handle
+                .block_on(async { self.control_port.get_display_snapshot().await })
+        }
+
+        fn clear_line(&self, y: u16) -> PyResult<()> {
+            self.runtime_handle.block_on(async {
+                self.control_port.clear_line(y).await;
+            });
+            Ok(())
+        }
+
+        fn write_display_wrap(&self, x: u16, y: u16, text: &str) -> PyResult<()> {
+            self.runtime_handle.block_on(async {
+                self.control_port.write_display_wrap(x, y, text).await;
+            });
+            Ok(())
+        }
+
         fn commit_display(&self) -> PyResult<()> {
             self.runtime_handle
                 .block_on(async { self.control_port.commit_display().await })
@@ -197,6 +310,13 @@ mod control_port_rs {
             Ok(())
         }
 
+        fn fade_leds(&self, target: Vec<(u8, u8, u8)>, duration_ms: u32) -> PyResult<()> {
+            self.runtime_handle.block_on(async {
+                self.control_port.fade_leds(target, duration_ms).await;
+            });
+            Ok(())
+        }
+
         fn set_backlights(&self, states: Vec<bool>) -> PyResult<()> {
             self.runtime_handle.block_on(async {
                 self.control_port.set_backlights(states).await;
@@ -218,6 +338,45 @@ mod control_port_rs {
             Ok(button_receiver)
         }
 
+        // Pull-based alternative to register_button_callback for callers that poll, e.g. from an
+        // asyncio executor
+        fn button_events(&self) -> ButtonEventStream {
+            ButtonEventStream {
+                runtime_handle: self.runtime_handle.clone(),
+                receiver: Arc::new(tokio::sync::Mutex::new(
+                    self.control_port.button_broadcast.subscribe(),
+                )),
+                shut_down: self.control_port.subscribe_shutdown(),
+                closed: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        // Analog inputs reported alongside button states, as a list of floats
+        fn register_analog_callback(&self, callback: PyObject) -> PyResult<AnalogEventReceiver> {
+            let receiver = self.control_port.analog_broadcast.subscribe();
+            let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+            let callback = Arc::new(callback);
+
+            Ok(AnalogEventReceiver {
+                runtime_handle: self.runtime_handle.clone(),
+                receiver,
+                callback,
+            })
+        }
+
+        // Unrecognized JSON messages (e.g. custom firmware types) are passed to the callback as a JSON string
+        fn register_message_callback(&self, callback: PyObject) -> PyResult<RawMessageReceiver> {
+            let receiver = self.control_port.raw_message_broadcast.subscribe();
+            let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+            let callback = Arc::new(callback);
+
+            Ok(RawMessageReceiver {
+                runtime_handle: self.runtime_handle.clone(),
+                receiver,
+                callback,
+            })
+        }
+
         fn dip(&self) -> String {
             self.control_port.dip.clone()
         }
@@ -252,19 +411,161 @@ mod control_port_rs {
             self.runtime_handle.spawn(async move {
                 loop {
                     let mut receiver_guard = receiver.lock().await;
-                    match receiver_guard.recv().await {
+                    match recv_button_state(&mut receiver_guard).await {
                         Ok(buttons) => {
                             let callback = callback.clone();
                             runtime_handle.spawn_blocking(move || {
                                 Python::with_gil(|py| {
                                     if let Err(e) = callback.call1(py, (buttons,)) {
-                                        println!("[RUST-DEBUG] Button callback error: {}", e);
+                                        log_background_error(&format!(
+                                            "Button callback error: {}",
+                                            e
+                                        ));
+                                    }
+                                });
+                            });
+                        }
+                        Err(e) => {
+                            tracing::debug!("Button event receiver stopped: {:?}", e);
+                            break;
+                        }
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+
+    #[pyclass(name = "ButtonEventStream")]
+    struct ButtonEventStream {
+        runtime_handle: tokio::runtime::Handle,
+        receiver: Arc<tokio::sync::Mutex<tokio::sync::broadcast::Receiver<Vec<bool>>>>,
+        // The broadcast sender lives as long as the ControlPort, so shutdown is watched for
+        // separately
+        shut_down: tokio::sync::watch::Receiver<bool>,
+        closed: Arc<AtomicBool>,
+    }
+
+    #[pymethods]
+    impl ButtonEventStream {
+        // Wait for the next button state without holding the GIL. Returns None on timeout or
+        // once the control port has shut down (see is_closed).
+        #[pyo3(signature = (timeout_ms=None))]
+        fn next_event(&self, py: Python<'_>, timeout_ms: Option<u64>) -> Option<Vec<bool>> {
+            if self.closed.load(Ordering::Relaxed) {
+                return None;
+            }
+            let receiver = self.receiver.clone();
+            let mut shut_down = self.shut_down.clone();
+            let closed = self.closed.clone();
+            py.allow_threads(|| {
+                self.runtime_handle.block_on(async move {
+                    let mut receiver = receiver.lock().await;
+                    let recv = async {
+                        // Events already queued are still delivered after shutdown
+                        let buttons = tokio::select! {
+                            biased;
+                            buttons = recv_button_state(&mut receiver) => buttons.ok(),
+                            _ = shut_down.wait_for(|&shut_down| shut_down) => None,
+                        };
+                        if buttons.is_none() {
+                            closed.store(true, Ordering::Relaxed);
+                        }
+                        buttons
+                    };
+                    match timeout_ms {
+                        Some(ms) => timeout(Duration::from_millis(ms), recv)
+                            .await
+                            .unwrap_or(None),
+                        None => recv.await,
+                    }
+                })
+            })
+        }
+
+        fn is_closed(&self) -> bool {
+            self.closed.load(Ordering::Relaxed)
+        }
+    }
+
+    #[pyclass(name = "AnalogEventReceiver")]
+    struct AnalogEventReceiver {
+        runtime_handle: tokio::runtime::Handle,
+        receiver: Arc<tokio::sync::Mutex<tokio::sync::broadcast::Receiver<Vec<f32>>>>,
+        callback: Arc<PyObject>,
+    }
+
+    #[pymethods]
+    impl AnalogEventReceiver {
+        fn start_listening(&self) -> PyResult<()> {
+            let receiver = self.receiver.clone();
+            let callback = self.callback.clone();
+            let runtime_handle = self.runtime_handle.clone();
+
+            self.runtime_handle.spawn(async move {
+                loop {
+                    let mut receiver_guard = receiver.lock().await;
+                    match receiver_guard.recv().await {
+                        Ok(values) => {
+                            let callback = callback.clone();
+                            runtime_handle.spawn_blocking(move || {
+                                Python::with_gil(|py| {
+                                    if let Err(e) = callback.call1(py, (values,)) {
+                                        log_background_error(&format!(
+                                            "Analog callback error: {}",
+                                            e
+                                        ));
+                                    }
+                                });
+                            });
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(e) => {
+                            tracing::debug!("Analog event receiver stopped: {:?}", e);
+                            break;
+                        }
+                    }
+                }
+            });
+            Ok(())
+        }
+    }
+
+    #[pyclass(name = "RawMessageReceiver")]
+    struct RawMessageReceiver {
+        runtime_handle: tokio::runtime::Handle,
+        receiver: Arc<tokio::sync::Mutex<tokio::sync::broadcast::Receiver<serde_json::Value>>>,
+        callback: Arc<PyObject>,
+    }
+
+    #[pymethods]
+    impl RawMessageReceiver {
+        fn start_listening(&self) -> PyResult<()> {
+            let receiver = self.receiver.clone();
+            let callback = self.callback.clone();
+            let runtime_handle = self.runtime_handle.clone();
+
+            self.runtime_handle.spawn(async move {
+                loop {
+                    let mut receiver_guard = receiver.lock().await;
+                    match receiver_guard.recv().await {
+                        Ok(json) => {
+                            let callback = callback.clone();
+                            let json_str = json.to_string();
+                            runtime_handle.spawn_blocking(move || {
+                                Python::with_gil(|py| {
+                                    if let Err(e) = callback.call1(py, (json_str,)) {
+                                        log_background_error(&format!(
+                                            "Message callback error: {}",
+                                            e
+                                        ));
                                     }
                                 });
                             });
                         }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
                         Err(e) => {
-                            println!("[RUST-DEBUG] Button event receiver error: {:?}", e);
+                            tracing::debug!("Raw message receiver stopped: {:?}", e);
                             break;
                         }
                     }
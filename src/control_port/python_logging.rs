@@ -0,0 +1,178 @@
+use pyo3::prelude::*;
+use std::fmt::{self, Write as _};
+use std::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+// One tracing event, ready to hand to Python's logging module
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    // Python logging level number (logging.DEBUG, logging.INFO, ...)
+    pub level: i32,
+    // The event's target as a dotted logger name, e.g. "control_port_rs.control_port"
+    pub logger: String,
+    pub message: String,
+}
+
+// Tracing layer that turns events into LogRecords. Records go over a channel instead of being
+// logged in place, so a task emitting an event never has to wait for the GIL.
+pub struct PythonLoggingLayer {
+    records: mpsc::Sender<LogRecord>,
+}
+
+impl PythonLoggingLayer {
+    pub fn new() -> (Self, mpsc::Receiver<LogRecord>) {
+        let (records, records_rx) = mpsc::channel();
+        (Self { records }, records_rx)
+    }
+}
+
+fn python_level(level: &Level) -> i32 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+// Formatted fields of a span, kept in its extensions for the events inside it
+struct SpanFields(String);
+
+#[derive(Default)]
+struct FieldFormatter {
+    message: String,
+    fields: String,
+}
+
+impl FieldFormatter {
+    fn push_field(&mut self, name: &str, value: fmt::Arguments<'_>) {
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={}", name, value);
+    }
+}
+
+impl Visit for FieldFormatter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.push_field(field.name(), format_args!("{}", value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            self.push_field(field.name(), format_args!("{:?}", value));
+        }
+    }
+}
+
+impl<S> Layer<S> for PythonLoggingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut formatter = FieldFormatter::default();
+        attrs.record(&mut formatter);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(formatter.fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Spans read like the fmt subscriber's: control_port{dip=3}:connection{addr=...}: message
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                message.push_str(span.name());
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    if !fields.is_empty() {
+                        let _ = write!(message, "{{{}}}", fields);
+                    }
+                }
+                message.push(':');
+            }
+            message.push(' ');
+        }
+
+        let mut formatter = FieldFormatter::default();
+        event.record(&mut formatter);
+        message.push_str(&formatter.message);
+        if !formatter.fields.is_empty() {
+            message.push(' ');
+            message.push_str(&formatter.fields);
+        }
+
+        let metadata = event.metadata();
+        let _ = self.records.send(LogRecord {
+            level: python_level(metadata.level()),
+            logger: metadata.target().replace("::", "."),
+            message,
+        });
+    }
+}
+
+// Install the forwarding layer as the global subscriber, passing on events at `level` and above.
+// Returns false if a global subscriber was already installed.
+pub fn init(level: LevelFilter) -> bool {
+    let (layer, records) = PythonLoggingLayer::new();
+    let subscriber = tracing_subscriber::registry().with(layer.with_filter(level));
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        return false;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("control_port_rs-logging".to_string())
+        .spawn(move || {
+            for record in records {
+                let logged = Python::with_gil(|py| -> PyResult<()> {
+                    py.import("logging")?
+                        .call_method1("getLogger", (record.logger.as_str(),))?
+                        .call_method1("log", (record.level, record.message.as_str()))?;
+                    Ok(())
+                });
+                if logged.is_err() {
+                    eprintln!("{}", record.message);
+                }
+            }
+        });
+    spawned.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_become_records_with_span_context() {
+        let (layer, records) = PythonLoggingLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("control_port", dip = "3");
+            let _entered = span.enter();
+            tracing::warn!(attempt = 2, "Connect failed: {}", "refused");
+        });
+
+        assert_eq!(
+            records.try_recv().unwrap(),
+            LogRecord {
+                level: 30,
+                logger: "control_port_rs.python_logging.tests".to_string(),
+                message: "control_port{dip=3}: Connect failed: refused attempt=2".to_string(),
+            }
+        );
+        assert!(records.try_recv().is_err());
+    }
+}
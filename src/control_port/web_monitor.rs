@@ -1,19 +1,22 @@
-use crate::control_port::{ControlPortManager, ControlPortStats, LogEntry};
+use crate::control_port::{ControlPortManager, ControlPortStats, LogDirection, LogEntry, LogKind};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{Html, Json},
     routing::get,
     Router,
 };
+use serde::Deserialize;
 use serde_json::json;
 use std::sync::Arc;
+use std::time::Instant;
 use tower_http::cors::CorsLayer;
 
 pub struct WebMonitor {
     control_port_manager: Arc<ControlPortManager>,
     log_buffer_size: usize,
     bind_address: String,
+    started_at: Instant,
 }
 
 impl WebMonitor {
@@ -22,6 +25,7 @@ impl WebMonitor {
             control_port_manager,
             log_buffer_size: 1000,               // Default log buffer size
             bind_address: "0.0.0.0".to_string(), // Default bind address
+            started_at: Instant::now(),
         }
     }
 
@@ -36,11 +40,17 @@ impl WebMonitor {
     }
 
     pub fn create_router(&self) -> Router {
+        let started_at = self.started_at;
         Router::new()
             .route("/", get(dashboard_html))
+            .route("/health", get(move || health(started_at)))
             .route("/api/control_ports", get(get_control_ports))
             .route("/api/control_ports/:dip/logs", get(get_control_port_logs))
             .route("/api/control_ports/:dip/stats", get(get_control_port_stats))
+            .route(
+                "/api/control_ports/:dip/display",
+                get(get_control_port_display),
+            )
             .with_state(self.control_port_manager.clone())
             .layer(CorsLayer::permissive())
     }
@@ -71,6 +81,11 @@ impl WebMonitor {
     }
 }
 
+// Liveness probe: cheap enough to poll often, unlike the stats endpoints
+async fn health(started_at: Instant) -> Json<serde_json::Value> {
+    Json(json!({ "ok": true, "uptime": started_at.elapsed().as_secs() }))
+}
+
 async fn dashboard_html() -> Html<&'static str> {
     Html(
         r#"<!DOCTYPE html>
@@ -92,6 +107,9 @@ async fn dashboard_html() -> Html<&'static str> {
         .log-outgoing { color: green; }
         .log-error { color: red; }
         .log-info { color: #666; }
+        .log-kind { color: #999; }
+        .rate-high { color: red; font-weight: bold; }
+        .log-kind-parse_error { font-weight: bold; }
         .refresh-btn { background: #667eea; color: white; border: none; padding: 8px 16px; border-radius: 4px; cursor: pointer; }
         .heartbeat-indicator {
             display: inline-block;
@@ -158,6 +176,7 @@ async fn dashboard_html() -> Html<&'static str> {
         }
         .scroll-indicator.auto { color: #4CAF50; }
         .scroll-indicator.manual { color: #FF9800; }
+        .lcd-preview { display: inline-block; background: #1e3a1e; color: #b8f5b8; font-family: monospace; font-size: 12px; line-height: 1.2; padding: 6px 8px; border-radius: 4px; margin: 0 0 10px 0; white-space: pre; }
     </style>
 </head>
 <body>
@@ -169,16 +188,25 @@ async fn dashboard_html() -> Html<&'static str> {
         <div style="text-align: center; padding: 40px;">Loading control port data...</div>
     </div>
     <script>
+        // Relative to wherever the dashboard is mounted, e.g. /control on a combined server
+        const API_BASE = window.location.pathname.replace(/\/$/, '');
         async function fetchControlPorts() {
-            const response = await fetch('/api/control_ports');
+            const response = await fetch(`${API_BASE}/api/control_ports`);
             return response.ok ? (await response.json()).control_ports : [];
         }
         async function fetchLogs(dip) {
-            const response = await fetch(`/api/control_ports/${dip}/logs`);
+            const response = await fetch(`${API_BASE}/api/control_ports/${dip}/logs`);
             return response.ok ? await response.json() : [];
         }
+        async function fetchDisplay(dip) {
+            const response = await fetch(`${API_BASE}/api/control_ports/${dip}/display`);
+            return response.ok ? await response.json() : [];
+        }
+        function escapeHtml(text) {
+            return text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;');
+        }
         async function fetchHeartbeat(dip) {
-            const response = await fetch(`/api/control_ports/${dip}/stats`);
+            const response = await fetch(`${API_BASE}/api/control_ports/${dip}/stats`);
             return response.ok ? await response.json() : {
                 heartbeat_received_active: false,
                 noop_sent_active: false,
@@ -195,12 +223,33 @@ async fn dashboard_html() -> Html<&'static str> {
             if (elapsed < 3600) return `${Math.floor(elapsed / 60)}m ago`;
             return `${Math.floor(elapsed / 3600)}h ago`;
         }
+        function formatDuration(seconds) {
+            if (seconds === null || seconds === undefined) return '—';
+            if (seconds < 60) return `${seconds}s`;
+            if (seconds < 3600) return `${Math.floor(seconds / 60)}m ${seconds % 60}s`;
+            return `${Math.floor(seconds / 3600)}h ${Math.floor((seconds % 3600) / 60)}m`;
+        }
+        function formatFirmware(controlPort) {
+            const version = controlPort.firmware_version || 'unknown';
+            const caps = controlPort.capabilities;
+            if (!caps) return version;
+            const details = [];
+            if (caps.lcd_width && caps.lcd_height) details.push(`LCD ${caps.lcd_width}×${caps.lcd_height}`);
+            if (caps.led_count !== null && caps.led_count !== undefined) details.push(`${caps.led_count} LEDs`);
+            return details.length > 0 ? `${version} (${details.join(', ')})` : version;
+        }
         function formatBytes(bytes) { return bytes < 1024 ? bytes + ' B' : Math.round(bytes/1024) + ' KB'; }
         function formatThroughput(bps) {
             if (bps < 1024) return bps.toFixed(1) + ' B/s';
             if (bps < 1024*1024) return (bps/1024).toFixed(1) + ' KB/s';
             return (bps/(1024*1024)).toFixed(1) + ' MB/s';
         }
+        // Incoming rates above this (heartbeats are ~1/s) usually mean a chatty controller
+        const HIGH_MESSAGE_RATE = 20;
+        function formatMessageRate(rate) {
+            const text = rate.toFixed(1) + ' msg/s';
+            return rate > HIGH_MESSAGE_RATE ? `<span class="rate-high">${text}</span>` : text;
+        }
 
         // Smart scrolling state management
         const scrollStates = new Map();
@@ -245,6 +294,7 @@ async fn dashboard_html() -> Html<&'static str> {
             const cards = await Promise.all(controlPorts.map(async controlPort => {
                 const logs = (await fetchLogs(controlPort.dip)).slice(-10); // Show last 10 filtered messages
                 const heartbeat = await fetchHeartbeat(controlPort.dip);
+                const display = await fetchDisplay(controlPort.dip);
                 const statusClass = controlPort.connected ? 'status-connected' : 'status-disconnected';
                 const statusText = controlPort.connected ? 'Connected' : 'Disconnected';
                 const heartbeatReceivedClass = heartbeat.heartbeat_received_active ? 'heartbeat-active' : '';
@@ -269,15 +319,20 @@ async fn dashboard_html() -> Html<&'static str> {
                             <span class="heartbeat-time">${formatTimeElapsed(heartbeat.last_noop_sent)}</span>
                         </div>
                         <p><strong>Address:</strong> ${controlPort.ip}:${controlPort.port}</p>
-                        <p><strong>Messages:</strong> ↑${controlPort.messages_sent} ↓${controlPort.messages_received}</p>
+                        <p><strong>Messages:</strong> ↑${controlPort.messages_sent} ↓${controlPort.messages_received} (${formatMessageRate(controlPort.messages_received_per_sec || 0)})</p>
+                        <p><strong>Queued:</strong> <span class="${controlPort.pending_messages > 100 ? 'status-disconnected' : ''}">${controlPort.pending_messages}</span></p>
                         <p><strong>Data:</strong> ↑${formatBytes(controlPort.bytes_sent)} ↓${formatBytes(controlPort.bytes_received)}</p>
                         <p><strong>Throughput:</strong> ↑${formatThroughput(controlPort.throughput_sent_bps || 0)} ↓${formatThroughput(controlPort.throughput_received_bps || 0)}</p>
+                        <p><strong>Parse Errors:</strong> ${controlPort.parse_errors}</p>
+                        <p><strong>Uptime:</strong> ${formatDuration(controlPort.current_uptime_seconds)} <strong>Disconnects:</strong> ${controlPort.disconnect_count}</p>
+                        <p><strong>Firmware:</strong> ${formatFirmware(controlPort)}</p>
+                        ${display.length > 0 ? `<pre class="lcd-preview" title="LCD contents">${display.map(escapeHtml).join('\n')}</pre>` : ''}
                         <div class="logs-container" id="logs-${controlPort.dip}" onscroll="saveScrollState('${controlPort.dip}', this)">
                             <div class="logs-header">
                                 <strong>Recent Messages (heartbeats filtered)</strong>
                                 <span class="scroll-indicator" id="scroll-indicator-${controlPort.dip}" title="Auto-scroll status">●</span>
                             </div>
-                            ${logs.length > 0 ? logs.map(log => `<div class="log-entry log-${log.direction}">${formatTime(log.timestamp)} ${log.direction}: ${log.message}</div>`).join('') : '<div class="log-entry log-info">No recent activity</div>'}
+                            ${logs.length > 0 ? logs.map(log => `<div class="log-entry log-${log.direction} log-kind-${log.kind}">${formatTime(log.timestamp)} ${log.direction} <span class="log-kind">[${log.kind}]</span>: ${log.message}</div>`).join('') : '<div class="log-entry log-info">No recent activity</div>'}
                         </div>
                     </div>
                 `;
@@ -353,25 +408,43 @@ async fn get_control_ports(
     Ok(Json(json!({ "control_ports": stats })))
 }
 
+// Query parameters for the logs endpoint, e.g. `?direction=error&include_heartbeat=true` or
+// `?kind=button`
+#[derive(Debug, Default, Deserialize)]
+struct LogQuery {
+    direction: Option<LogDirection>,
+    kind: Option<LogKind>,
+    #[serde(default)]
+    include_heartbeat: bool,
+}
+
+impl LogQuery {
+    fn matches(&self, log: &LogEntry) -> bool {
+        // Asking for a keepalive kind explicitly includes it; errors are never hidden as keepalive
+        let kind_matches = match self.kind {
+            Some(kind) => log.kind == kind,
+            None => {
+                self.include_heartbeat
+                    || log.direction == LogDirection::Error
+                    || !log.kind.is_keepalive()
+            }
+        };
+        self.direction
+            .is_none_or(|direction| log.direction == direction)
+            && kind_matches
+    }
+}
+
 async fn get_control_port_logs(
     Path(dip): Path<String>,
+    Query(query): Query<LogQuery>,
     State(manager): State<Arc<ControlPortManager>>,
 ) -> Result<Json<Vec<LogEntry>>, StatusCode> {
     if let Some(control_port) = manager.get_control_port(&dip) {
-        let logs = control_port.logs.read().await;
-
-        // Filter out heartbeat and noop messages and limit buffer size
-        let filtered_logs: Vec<LogEntry> = logs
-            .iter()
-            .filter(|log| {
-                // Filter out heartbeat and noop messages
-                !log.message.contains("noop")
-                    && !log.message.contains("Noop")
-                    && !log.message.contains("heartbeat")
-                    && !log.message.contains("Heartbeat")
-            })
-            .cloned()
-            .collect();
+        // Heartbeat/noop traffic is hidden unless asked for
+        let filtered_logs = control_port
+            .get_logs_matching(|log| query.matches(log))
+            .await;
 
         Ok(Json(filtered_logs))
     } else {
@@ -390,3 +463,65 @@ async fn get_control_port_stats(
         Err(StatusCode::NOT_FOUND)
     }
 }
+
+// Committed LCD contents, one string per row (20x4 unless the controller reported otherwise)
+async fn get_control_port_display(
+    Path(dip): Path<String>,
+    State(manager): State<Arc<ControlPortManager>>,
+) -> Result<Json<Vec<String>>, StatusCode> {
+    if let Some(control_port) = manager.get_control_port(&dip) {
+        Ok(Json(control_port.get_display_snapshot().await))
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control_port::LogKind;
+    use chrono::Utc;
+
+    fn log(direction: LogDirection, kind: LogKind, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            direction,
+            kind,
+            message: message.to_string(),
+            raw_data: None,
+        }
+    }
+
+    fn parse_query(query: &str) -> LogQuery {
+        let uri = format!("http://localhost/logs?{}", query).parse().unwrap();
+        Query::<LogQuery>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn test_log_query_filters_by_structured_fields() {
+        let noop = log(LogDirection::Outgoing, LogKind::Noop, "Sent: Noop");
+        let error = log(LogDirection::Error, LogKind::Other, "Write error");
+        // Mentions noop in its text but is not keepalive traffic
+        let info = log(LogDirection::Info, LogKind::Other, "info about noop");
+
+        let heartbeat_error = log(LogDirection::Error, LogKind::Noop, "Heartbeat failed");
+
+        let default_query = parse_query("");
+        assert!(!default_query.matches(&noop));
+        assert!(default_query.matches(&error));
+        assert!(default_query.matches(&info));
+        assert!(default_query.matches(&heartbeat_error));
+
+        let errors_only = parse_query("direction=error");
+        assert!(errors_only.matches(&error));
+        assert!(!errors_only.matches(&info));
+
+        let with_heartbeat = parse_query("direction=outgoing&include_heartbeat=true");
+        assert!(with_heartbeat.matches(&noop));
+        assert!(!with_heartbeat.matches(&error));
+
+        let noops = parse_query("kind=noop");
+        assert!(noops.matches(&noop));
+        assert!(!noops.matches(&info));
+    }
+}
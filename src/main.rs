@@ -1,14 +1,20 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::net::{SocketAddr, UdpSocket};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::sync::{OnceLock, RwLock};
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio::time::interval; // For channels between MIDI callback and MIDI processing task
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use midir::{Ignore, MidiInput, MidiInputPort, MidiOutput, MidiOutputConnection, MidiOutputPort};
 use rosc::{decoder::decode_udp, encoder, OscMessage, OscPacket, OscType};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn, Level};
 use tracing_subscriber::FmtSubscriber; // For argument parsing
 
@@ -27,26 +33,199 @@ const LED_RED: u8 = 3;
 const LED_ORANGE: u8 = 5;
 const LED_BLUE_ISH: u8 = 6;
 
-// OSC Buffer Size
+// Default OSC Buffer Size
 const OSC_BUF_SIZE: usize = 1536; // A common buffer size for OSC over UDP
 
-lazy_static::lazy_static! {
-    static ref NOTE_GRID: [[u8; NUM_COLS]; NUM_ROWS] = {
+// Rejected OSC input reports: address of the reply messages and the most distinct
+// (address, reason) pairs kept between reports
+const OSC_ERROR_REPORT_ADDR: &str = "/mapper/rejected";
+const OSC_ERROR_REPORT_MAX_ENTRIES: usize = 32;
+
+// MIDI note of each pad in the visible grid; row 0 is the top row
+type NoteGrid = [[u8; NUM_COLS]; NUM_ROWS];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum NoteGridLayout {
+    // APC MINI: bottom-left origin, 8 notes per row
+    ApcMini,
+    // Bottom-left origin, 16 notes per row. Its third row from the top (notes 80-87) covers
+    // the APC MINI bank notes, so its bank buttons default to the unused notes 8-15 instead.
+    Wide16,
+}
+
+impl NoteGridLayout {
+    fn grid(self) -> NoteGrid {
+        let row_stride = match self {
+            NoteGridLayout::ApcMini => 8,
+            NoteGridLayout::Wide16 => 16,
+        };
         let mut grid = [[0u8; NUM_COLS]; NUM_ROWS];
-        for r in 0..NUM_ROWS {
-            for c in 0..NUM_COLS {
-                grid[r][c] = ((NUM_ROWS - 1 - r) * 8 + c) as u8;
+        for (r, row) in grid.iter_mut().enumerate() {
+            for (c, note) in row.iter_mut().enumerate() {
+                *note = ((NUM_ROWS - 1 - r) * row_stride + c) as u8;
             }
         }
         grid
-    };
+    }
+
+    // Bank select notes used unless --*-bank-note-base is given
+    fn bank_notes(self) -> BankNotes {
+        match self {
+            NoteGridLayout::ApcMini => APC_MINI_BANK_NOTES,
+            NoteGridLayout::Wide16 => WIDE16_BANK_NOTES,
+        }
+    }
+}
+
+// The active note grid, chosen at startup; the APC MINI layout unless overridden
+static NOTE_GRID: OnceLock<NoteGrid> = OnceLock::new();
+
+fn note_grid() -> &'static NoteGrid {
+    NOTE_GRID.get_or_init(|| NoteGridLayout::ApcMini.grid())
+}
+
+// Visible (row, col) of the pad that sends `note`, if any
+fn note_grid_position(note: u8) -> Option<(usize, usize)> {
+    note_grid().iter().enumerate().find_map(|(r, row)| {
+        row.iter()
+            .position(|&grid_note| grid_note == note)
+            .map(|c| (r, c))
+    })
+}
+
+// MIDI notes of a row of bank select buttons: `count` consecutive notes starting at `base`.
+// Banks past `count` can't be selected from the controller and have no LED.
+#[derive(Clone, Copy, Debug)]
+struct BankNoteRange {
+    base: u8,
+    count: usize,
+}
+
+impl BankNoteRange {
+    // Bank selected by `note`, if it is one of these buttons
+    fn bank(self, note: u8) -> Option<usize> {
+        note.checked_sub(self.base)
+            .map(usize::from)
+            .filter(|&bank| bank < self.count)
+    }
+
+    // Note of the button (and LED) for `bank`, if it has one
+    fn note(self, bank: usize) -> Option<u8> {
+        (bank < self.count).then(|| self.base + bank as u8)
+    }
+
+    fn contains(self, note: u8) -> bool {
+        self.bank(note).is_some()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BankNotes {
+    lfo: BankNoteRange,
+    effect: BankNoteRange,
+}
+
+// APC MINI: LFO banks on notes 82-85, effect banks on 86-89
+const APC_MINI_BANK_NOTES: BankNotes = BankNotes {
+    lfo: BankNoteRange {
+        base: 82,
+        count: NUM_LFO_BANKS,
+    },
+    effect: BankNoteRange {
+        base: 86,
+        count: NUM_EFFECT_BANKS,
+    },
+};
+
+// Wide16: LFO banks on notes 8-11, effect banks on 12-15, right of the bottom grid row
+const WIDE16_BANK_NOTES: BankNotes = BankNotes {
+    lfo: BankNoteRange {
+        base: 8,
+        count: NUM_LFO_BANKS,
+    },
+    effect: BankNoteRange {
+        base: 12,
+        count: NUM_EFFECT_BANKS,
+    },
+};
+
+// The active bank select notes, chosen at startup; the APC MINI notes unless overridden
+static BANK_NOTES: OnceLock<BankNotes> = OnceLock::new();
+
+fn bank_notes() -> &'static BankNotes {
+    BANK_NOTES.get_or_init(|| APC_MINI_BANK_NOTES)
+}
+
+// Checks the --*-bank-note-* options: at most one button per bank, valid MIDI notes and no
+// note used by both rows
+fn validate_bank_notes(bank_notes: &BankNotes) -> Result<(), String> {
+    for (name, range, max_count) in [
+        ("LFO", bank_notes.lfo, NUM_LFO_BANKS),
+        ("effect", bank_notes.effect, NUM_EFFECT_BANKS),
+    ] {
+        if range.count == 0 || range.count > max_count {
+            return Err(format!(
+                "{} bank note count {} out of range 1-{}",
+                name, range.count, max_count
+            ));
+        }
+        if range.base as usize + range.count > 128 {
+            return Err(format!(
+                "{} bank notes {}-{} exceed MIDI note 127",
+                name,
+                range.base,
+                range.base as usize + range.count - 1
+            ));
+        }
+    }
+    if (0..bank_notes.lfo.count).any(|bank| {
+        bank_notes
+            .lfo
+            .note(bank)
+            .is_some_and(|note| bank_notes.effect.contains(note))
+    }) {
+        return Err("LFO and effect bank notes overlap".to_string());
+    }
+    Ok(())
+}
+
+// Reads a note grid from a JSON file: NUM_ROWS arrays of NUM_COLS notes, top row first
+fn load_note_grid(path: &str) -> Result<NoteGrid, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open note grid {}: {}", path, e))?;
+    let rows: Vec<Vec<u8>> = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Failed to parse note grid {}: {}", path, e))?;
+    if rows.len() != NUM_ROWS || rows.iter().any(|row| row.len() != NUM_COLS) {
+        return Err(format!(
+            "Note grid {} must be {} rows of {} notes",
+            path, NUM_ROWS, NUM_COLS
+        ));
+    }
+
+    let mut grid = [[0u8; NUM_COLS]; NUM_ROWS];
+    let mut seen = [false; 128];
+    for (r, row) in rows.iter().enumerate() {
+        for (c, &note) in row.iter().enumerate() {
+            if note > 127 {
+                return Err(format!(
+                    "Note grid {}: note {} is not a MIDI note",
+                    path, note
+                ));
+            }
+            if seen[note as usize] {
+                return Err(format!("Note grid {}: note {} is used twice", path, note));
+            }
+            seen[note as usize] = true;
+            grid[r][c] = note;
+        }
+    }
+    Ok(grid)
 }
 
 // --- LedState for diffing MIDI messages ---
 struct LedState {
     grid: [[u8; NUM_COLS]; NUM_ROWS], // Velocities for the 8x8 visible grid
-    lfo_banks: [u8; NUM_LFO_BANKS],   // Velocities for LFO bank LEDs (notes 82-85)
-    effect_banks: [u8; NUM_EFFECT_BANKS], // Velocities for Effect bank LEDs (notes 86-89)
+    lfo_banks: [u8; NUM_LFO_BANKS],   // Velocities for LFO bank LEDs (notes 82-85 by default)
+    effect_banks: [u8; NUM_EFFECT_BANKS], // Velocities for Effect bank LEDs (notes 86-89 by default)
 }
 
 impl LedState {
@@ -58,6 +237,16 @@ impl LedState {
         }
     }
 
+    // Forget what the controller is showing, so the next refresh resends every LED
+    fn forget(&mut self) {
+        // No LED velocity is 0xFF, so every LED compares as changed
+        *self = Self {
+            grid: [[u8::MAX; NUM_COLS]; NUM_ROWS],
+            lfo_banks: [u8::MAX; NUM_LFO_BANKS],
+            effect_banks: [u8::MAX; NUM_EFFECT_BANKS],
+        };
+    }
+
     // Sends a MIDI note for a grid LED if its state has changed.
     // r_vis and c_vis are 0-indexed for the visible 8x8 grid.
     fn send_grid_note_if_changed(
@@ -69,7 +258,7 @@ impl LedState {
     ) {
         if r_vis < NUM_ROWS && c_vis < NUM_COLS {
             // Bounds check for safety
-            let note = NOTE_GRID[r_vis][c_vis];
+            let note = note_grid()[r_vis][c_vis];
             if self.grid[r_vis][c_vis] != desired_velocity {
                 debug!(
                     "GRID LED CHANGE: Note {}, Vis ({},{}), From {}, To {}",
@@ -100,7 +289,9 @@ impl LedState {
     ) {
         if bank_idx < NUM_LFO_BANKS {
             // Bounds check
-            let note = (82 + bank_idx) as u8;
+            let Some(note) = bank_notes().lfo.note(bank_idx) else {
+                return; // No button for this bank
+            };
             if self.lfo_banks[bank_idx] != desired_velocity {
                 debug!(
                     "LFO BANK LED CHANGE: Note {}, Bank Idx {}, From {}, To {}",
@@ -131,7 +322,9 @@ impl LedState {
     ) {
         if bank_idx < NUM_EFFECT_BANKS {
             // Bounds check
-            let note = (86 + bank_idx) as u8;
+            let Some(note) = bank_notes().effect.note(bank_idx) else {
+                return; // No button for this bank
+            };
             if self.effect_banks[bank_idx] != desired_velocity {
                 debug!(
                     "EFFECT BANK LED CHANGE: Note {}, Bank Idx {}, From {}, To {}",
@@ -165,6 +358,310 @@ struct CliArgs {
     out_host: String,
     #[clap(long, default_value_t = 9001)]
     out_port: u16,
+    #[clap(long, default_value_t = OSC_BUF_SIZE)]
+    osc_buf_size: usize,
+    /// Divisor used to normalize integer LFO arguments into the 0..1 range
+    #[clap(long, default_value_t = 127.0)]
+    lfo_int_divisor: f32,
+    /// Log the effect indices and values included in every outgoing OSC bundle
+    #[clap(long)]
+    log_osc_bundles: bool,
+    /// Lower bound applied to every outgoing effect value
+    #[clap(long, default_value_t = 0.0)]
+    output_min: f32,
+    /// Upper bound applied to every outgoing effect value
+    #[clap(long, default_value_t = 1.0)]
+    output_max: f32,
+    /// Record state-changing MIDI events (bank switches, grid presses, faders) to this file
+    #[clap(long, conflicts_with = "replay")]
+    record: Option<String>,
+    /// Replay a recorded session instead of reading from the APC MINI
+    #[clap(long)]
+    replay: Option<String>,
+    /// Warn and resend every LED when no MIDI input arrives for this many seconds, in case the
+    /// APC power-cycled while its port stayed open
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    midi_silence_secs: Option<u64>,
+    /// Run without any MIDI hardware, reading events from stdin instead: `note <n>`,
+    /// `pad <row> <col>` (1-based, top row first) or `cc <n> <value>`, one per line
+    #[clap(long, conflicts_with = "replay")]
+    simulate: bool,
+    /// Send LFO values back to motorized faders as CC (only for controllers with motorized faders)
+    #[clap(long)]
+    fader_feedback: bool,
+    /// Sweep every grid and bank LED through each color at startup to check for dead pads
+    #[clap(long)]
+    led_selftest: bool,
+    /// Briefly flash the bank button when switching LFO or effect banks
+    #[clap(long)]
+    bank_flash: bool,
+    /// Which LFO bank's fader override wins when several banks override the same effect
+    #[clap(long, value_enum, default_value_t = FaderOverridePolicy::First)]
+    fader_override_policy: FaderOverridePolicy,
+    /// MIDI note layout of the pad grid
+    #[clap(long, value_enum, default_value_t = NoteGridLayout::ApcMini)]
+    note_grid_layout: NoteGridLayout,
+    /// JSON file with the pad grid's notes (8 rows of 8, top row first); overrides --note-grid-layout
+    #[clap(long)]
+    note_grid_file: Option<String>,
+    /// Transport for the OSC output; tcp uses OSC 1.0 length-prefix framing and reconnects
+    /// automatically, discard drops every packet (e.g. with --simulate and --log-osc-bundles)
+    #[clap(long, value_enum, default_value_t = OscTransport::Udp)]
+    osc_out_transport: OscTransport,
+    /// Local address:port for the UDP OSC output socket, e.g. to pin the source port for
+    /// firewall rules (default: any address, ephemeral port)
+    #[clap(long)]
+    osc_out_bind: Option<SocketAddr>,
+    /// Timetag on outgoing OSC bundles
+    #[clap(long, value_enum, default_value_t = OscTimetag::Immediate)]
+    osc_timetag: OscTimetag,
+    /// Delay added to the current time when --osc-timetag is offset
+    #[clap(long, default_value_t = 0)]
+    osc_timetag_offset_ms: u64,
+    /// Slow the OSC sender from 60Hz to 10Hz after this many ticks without any effect value
+    /// changing; it returns to 60Hz on the next change
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    idle_after_ticks: Option<u32>,
+    /// Address template for effect outputs. Placeholders (all 1-based): {index} is the global
+    /// effect number, {bank} the effect bank and {bank_index} the effect within its bank
+    #[clap(long, default_value = "/effect/{index}")]
+    osc_effect_address: String,
+    /// host:port to send summaries of rejected OSC input to (address, reason and count)
+    #[clap(long)]
+    osc_error_report: Option<String>,
+    /// Minimum time between rejected-input reports
+    #[clap(long, default_value_t = 1000)]
+    osc_error_report_interval_ms: u64,
+    /// MIDI note of the first LFO bank select button (default: 82, or 8 with the wide16 layout)
+    #[clap(long)]
+    lfo_bank_note_base: Option<u8>,
+    /// Number of LFO bank select buttons (consecutive notes)
+    #[clap(long, default_value_t = APC_MINI_BANK_NOTES.lfo.count)]
+    lfo_bank_note_count: usize,
+    /// MIDI note of the first effect bank select button (default: 86, or 12 with the wide16
+    /// layout)
+    #[clap(long)]
+    effect_bank_note_base: Option<u8>,
+    /// Number of effect bank select buttons (consecutive notes)
+    #[clap(long, default_value_t = APC_MINI_BANK_NOTES.effect.count)]
+    effect_bank_note_count: usize,
+    /// Effects (1-based, comma-separated) whose grid column allows several LFOs at once instead
+    /// of unmapping the others; also settable with /column_mode/<effect> "additive"|"exclusive"
+    #[clap(long, value_delimiter = ',')]
+    additive_columns: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OscTransport {
+    Udp,
+    Tcp,
+    Discard,
+}
+
+// Timetag written on outgoing bundles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OscTimetag {
+    // The OSC "immediately" tag (seconds 0, fraction 1)
+    Immediate,
+    // The current NTP time
+    Now,
+    // The current NTP time plus --osc-timetag-offset-ms
+    Offset,
+}
+
+// How to resolve an effect column that has fader overrides in more than one LFO bank
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum FaderOverridePolicy {
+    // Lowest-numbered bank wins
+    First,
+    // Highest-numbered bank wins
+    Last,
+    // The active LFO bank wins if it has an override, otherwise the lowest-numbered bank
+    CurrentBank,
+}
+
+impl FaderOverridePolicy {
+    // Picks the winning bank from the (ascending) banks overriding a column
+    fn select(self, overriding_banks: &[usize], active_lfo_bank: usize) -> Option<usize> {
+        match self {
+            FaderOverridePolicy::First => overriding_banks.first().copied(),
+            FaderOverridePolicy::Last => overriding_banks.last().copied(),
+            FaderOverridePolicy::CurrentBank => {
+                if overriding_banks.contains(&active_lfo_bank) {
+                    Some(active_lfo_bank)
+                } else {
+                    overriding_banks.first().copied()
+                }
+            }
+        }
+    }
+}
+
+// Settings for the incoming OSC stream
+struct OscInputConfig {
+    buf_size: usize,
+    int_divisor: f32,
+    error_report_addr: Option<SocketAddr>,
+    error_report_interval: Duration,
+    // For refreshing the LEDs after a bank switch requested over OSC
+    led_tx: mpsc::Sender<LedUpdateRequest>,
+}
+
+// Collects rejected OSC input and reports it back to the sender side, at most once per interval
+struct OscErrorReporter {
+    target: SocketAddr,
+    interval: Duration,
+    last_report: Option<std::time::Instant>,
+    // (address, reason) -> times rejected since the last report
+    pending: BTreeMap<(String, String), i32>,
+}
+
+impl OscErrorReporter {
+    fn new(target: SocketAddr, interval: Duration) -> Self {
+        Self {
+            target,
+            interval,
+            last_report: None,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, addr: &str, reason: &str) {
+        let key = (addr.to_string(), reason.to_string());
+        // A flood of distinct bad addresses only keeps the first few until the next report
+        if self.pending.len() >= OSC_ERROR_REPORT_MAX_ENTRIES && !self.pending.contains_key(&key) {
+            return;
+        }
+        *self.pending.entry(key).or_insert(0) += 1;
+    }
+
+    fn flush_if_due(&mut self, socket: &UdpSocket) {
+        if self.pending.is_empty()
+            || self
+                .last_report
+                .is_some_and(|last| last.elapsed() < self.interval)
+        {
+            return;
+        }
+        self.last_report = Some(std::time::Instant::now());
+
+        let content = std::mem::take(&mut self.pending)
+            .into_iter()
+            .map(|((addr, reason), count)| {
+                OscPacket::Message(OscMessage {
+                    addr: OSC_ERROR_REPORT_ADDR.to_string(),
+                    args: vec![
+                        OscType::String(addr),
+                        OscType::String(reason),
+                        OscType::Int(count),
+                    ],
+                })
+            })
+            .collect();
+        let bundle = OscPacket::Bundle(rosc::OscBundle {
+            timetag: rosc::OscTime {
+                seconds: 0,
+                fractional: 1,
+            }, // Represents "immediately"
+            content,
+        });
+        match encoder::encode(&bundle) {
+            Ok(encoded_bundle) => {
+                if let Err(e) = socket.send_to(&encoded_bundle, self.target) {
+                    error!("Failed to send OSC error report to {}: {}", self.target, e);
+                }
+            }
+            Err(e) => {
+                error!("Failed to encode OSC error report: {}", e);
+            }
+        }
+    }
+}
+
+// How mapping an LFO onto an effect column treats the LFOs already mapped to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnMode {
+    // Unmaps the other LFOs of the current bank from the column
+    Exclusive,
+    // Leaves other mappings in place
+    Additive,
+}
+
+impl ColumnMode {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "exclusive" => Some(ColumnMode::Exclusive),
+            "additive" => Some(ColumnMode::Additive),
+            _ => None,
+        }
+    }
+}
+
+// Settings for the outgoing OSC stream
+struct OscSenderConfig {
+    log_bundles: bool,
+    output_min: f32,
+    output_max: f32,
+    fader_override_policy: FaderOverridePolicy,
+    transport: OscTransport,
+    // Local address of the UDP output socket
+    bind_addr: SocketAddr,
+    // Output address of each effect, indexed like the mapping columns
+    effect_addresses: Vec<String>,
+    timetag: OscTimetag,
+    timetag_offset: Duration,
+    // Unchanged ticks before dropping to IDLE_TICK_PERIOD; None keeps the sender at 60Hz
+    idle_after_ticks: Option<u32>,
+}
+
+impl OscSenderConfig {
+    fn bundle_timetag(&self) -> rosc::OscTime {
+        let immediately = rosc::OscTime {
+            seconds: 0,
+            fractional: 1,
+        };
+        let at = match self.timetag {
+            OscTimetag::Immediate => return immediately,
+            OscTimetag::Now => std::time::SystemTime::now(),
+            OscTimetag::Offset => std::time::SystemTime::now() + self.timetag_offset,
+        };
+        rosc::OscTime::try_from(at).unwrap_or_else(|e| {
+            warn!("Falling back to an immediate OSC timetag: {:?}", e);
+            immediately
+        })
+    }
+}
+
+// Expands the --osc-effect-address template for every effect
+fn effect_addresses(template: &str) -> Result<Vec<String>, String> {
+    if !template.starts_with('/') {
+        return Err(format!(
+            "OSC effect address template {} must start with '/'",
+            template
+        ));
+    }
+    let addresses: Vec<String> = (0..TOTAL_COLS)
+        .map(|idx| {
+            template
+                .replace("{index}", &(idx + 1).to_string())
+                .replace("{bank_index}", &(idx % NUM_COLS + 1).to_string())
+                .replace("{bank}", &(idx / NUM_COLS + 1).to_string())
+        })
+        .collect();
+    if addresses[0].contains('{') || addresses[0].contains('}') {
+        return Err(format!(
+            "OSC effect address template {} has an unknown placeholder",
+            template
+        ));
+    }
+    let unique: std::collections::HashSet<&String> = addresses.iter().collect();
+    if unique.len() != addresses.len() {
+        return Err(format!(
+            "OSC effect address template {} doesn't give each effect its own address",
+            template
+        ));
+    }
+    Ok(addresses)
 }
 
 // --- Shared Application State (Refactored for Granular Locking & Atomics) ---
@@ -178,15 +675,30 @@ struct AppStateBanks {
 enum LedUpdateRequest {
     FullRefresh,
     BothRefresh,
+    // BothRefresh caused by pressing a bank button (note), which settles at `velocity`
+    BankSwitchRefresh { note: u8, velocity: u8 },
+    // BothRefresh that resends every LED, for a controller that may have reset its LEDs
+    ResyncRefresh,
     FaderColumnRefresh { actual_effect_idx: usize },
 }
 
 struct AppState {
     banks: Arc<AppStateBanks>, // No longer Mutex wrapped
     mapping: Arc<RwLock<Vec<Vec<bool>>>>,
+    // lfo_drivers[lfo_bank][effect]: the LFO that drives each effect when that LFO bank is
+    // active, derived from `mapping` so the OSC sender doesn't rescan it every tick
+    lfo_drivers: Arc<RwLock<Vec<Vec<Option<usize>>>>>,
+    // Per effect column
+    column_modes: Arc<RwLock<Vec<ColumnMode>>>,
     fader_override_active: Arc<RwLock<Vec<Vec<bool>>>>,
     fader_override_value: Arc<RwLock<Vec<Vec<f32>>>>,
     latest_lfo_values: Arc<RwLock<Vec<f32>>>,
+    // Set by a /state/meters query, answered by the OSC sender on its next tick
+    meters_requested: AtomicBool,
+    // Set by a /state/mapping query, answered by the OSC sender on its next tick
+    mapping_requested: AtomicBool,
+    // Named states saved by /snapshot/save for /snapshot/recall
+    snapshots: RwLock<HashMap<String, StateSnapshot>>,
 }
 
 impl AppState {
@@ -197,14 +709,175 @@ impl AppState {
                 current_effect_bank: AtomicUsize::new(0),
             }),
             mapping: Arc::new(RwLock::new(vec![vec![false; TOTAL_COLS]; TOTAL_ROWS])),
+            lfo_drivers: Arc::new(RwLock::new(vec![vec![None; TOTAL_COLS]; NUM_LFO_BANKS])),
+            column_modes: Arc::new(RwLock::new(vec![ColumnMode::Exclusive; TOTAL_COLS])),
             fader_override_active: Arc::new(RwLock::new(vec![
                 vec![false; TOTAL_COLS];
                 NUM_LFO_BANKS
             ])),
             fader_override_value: Arc::new(RwLock::new(vec![vec![0.0; TOTAL_COLS]; NUM_LFO_BANKS])),
             latest_lfo_values: Arc::new(RwLock::new(vec![0.0; TOTAL_ROWS])),
+            meters_requested: AtomicBool::new(false),
+            mapping_requested: AtomicBool::new(false),
+            snapshots: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Recompute the drivers of one effect column after its mapping changed
+    fn refresh_lfo_drivers(&self, mapping: &[Vec<bool>], effect: usize) {
+        let mut drivers = self.lfo_drivers.write().unwrap();
+        for (lfo_bank, bank_drivers) in drivers.iter_mut().enumerate() {
+            bank_drivers[effect] = lfo_driver(mapping, lfo_bank, effect);
+        }
+    }
+}
+
+// The highest visual row of `lfo_bank` mapped to `effect`, as a global LFO index
+fn lfo_driver(mapping: &[Vec<bool>], lfo_bank: usize, effect: usize) -> Option<usize> {
+    (0..NUM_ROWS)
+        .rev()
+        .map(|visual_row| lfo_bank * NUM_ROWS + visual_row)
+        .find(|&lfo| lfo < TOTAL_ROWS && mapping[lfo][effect])
+}
+
+// --- Session Recording / Replay ---
+// One line of a recorded session file (JSON lines)
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedMidiEvent {
+    elapsed_ms: u64,
+    message: Vec<u8>,
+}
+
+// How often process_midi_messages writes out buffered recording lines, idle or not; a crash or
+// Ctrl-C loses at most this much
+const RECORD_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+struct MidiRecorder {
+    writer: BufWriter<File>,
+    start: tokio::time::Instant,
+}
+
+impl MidiRecorder {
+    fn create(path: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            start: tokio::time::Instant::now(),
+        })
+    }
+
+    fn record(&mut self, message: &[u8]) {
+        let event = RecordedMidiEvent {
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+            message: message.to_vec(),
+        };
+        let result = serde_json::to_string(&event)
+            .map_err(std::io::Error::from)
+            .and_then(|line| writeln!(self.writer, "{}", line));
+        if let Err(e) = result {
+            warn!("Failed to record MIDI event {:?}: {}", message, e);
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            warn!("Failed to flush MIDI recording: {}", e);
+        }
+    }
+}
+
+impl Drop for MidiRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+// Feeds a recorded session into the MIDI processing channel with its original timing
+async fn replay_midi_session(path: String, midi_tx: mpsc::Sender<Vec<u8>>) -> Result<(), String> {
+    let file =
+        File::open(&path).map_err(|e| format!("Failed to open replay file {}: {}", path, e))?;
+    info!("Replaying MIDI session from {}", path);
+    let start = tokio::time::Instant::now();
+    let mut replayed = 0usize;
+
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read replay file {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: RecordedMidiEvent = match serde_json::from_str(&line) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("Skipping malformed replay line {}: {}", line_no + 1, e);
+                continue;
+            }
+        };
+        tokio::time::sleep_until(start + Duration::from_millis(event.elapsed_ms)).await;
+        if midi_tx.send(event.message).await.is_err() {
+            return Err("MIDI processing task stopped during replay".to_string());
+        }
+        replayed += 1;
+    }
+
+    info!("Replay of {} finished ({} events)", path, replayed);
+    Ok(())
+}
+
+// Turns one line of --simulate input into the MIDI message the APC would send
+fn parse_simulated_midi(line: &str) -> Result<Vec<u8>, String> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let number = |idx: usize, name: &str, max: usize| -> Result<usize, String> {
+        let value = parts
+            .get(idx)
+            .ok_or_else(|| format!("missing {}", name))?
+            .parse::<usize>()
+            .map_err(|_| format!("invalid {} '{}'", name, parts[idx]))?;
+        if value > max {
+            return Err(format!("{} {} out of range 0-{}", name, value, max));
+        }
+        Ok(value)
+    };
+    match parts.first().copied() {
+        Some("note") => Ok(vec![0x90, number(1, "note", 127)? as u8, 127]),
+        Some("pad") => {
+            let row = number(1, "row", NUM_ROWS)?;
+            let col = number(2, "column", NUM_COLS)?;
+            if row == 0 || col == 0 {
+                return Err("pad row and column are 1-based".to_string());
+            }
+            Ok(vec![0x90, note_grid()[row - 1][col - 1], 127])
+        }
+        Some("cc") => Ok(vec![
+            0xB0,
+            number(1, "controller", 127)? as u8,
+            number(2, "value", 127)? as u8,
+        ]),
+        _ => Err("expected note <n>, pad <row> <col> or cc <n> <value>".to_string()),
+    }
+}
+
+// Feeds MIDI events typed on stdin into the MIDI processing channel (--simulate)
+async fn simulate_midi_input(midi_tx: mpsc::Sender<Vec<u8>>) -> Result<(), String> {
+    info!("Simulating MIDI input from stdin (note <n>, pad <row> <col>, cc <n> <value>)");
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| format!("Failed to read simulated input: {}", e))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_simulated_midi(&line) {
+            Ok(message) => {
+                if midi_tx.send(message).await.is_err() {
+                    return Err("MIDI processing task stopped during simulation".to_string());
+                }
+            }
+            Err(e) => warn!("Ignoring simulated input '{}': {}", line.trim(), e),
         }
     }
+    info!("Simulated input closed");
+    Ok(())
 }
 
 // Define a common error type for the application
@@ -224,7 +897,74 @@ async fn main() -> Result<(), AppError> {
     let args = CliArgs::parse();
     info!("Starting ArtNet Mapper in Rust with args: {:?}", args);
 
+    // f32::clamp panics on NaN bounds, so catch them here rather than in the sender loop
+    if !args.output_min.is_finite() || !args.output_max.is_finite() {
+        return Err(AppError::from(format!(
+            "--output-min ({}) and --output-max ({}) must be finite",
+            args.output_min, args.output_max
+        )));
+    }
+    if args.output_min > args.output_max {
+        return Err(AppError::from(format!(
+            "--output-min ({}) must not exceed --output-max ({})",
+            args.output_min, args.output_max
+        )));
+    }
+
+    let effect_addresses = effect_addresses(&args.osc_effect_address).map_err(AppError::from)?;
+
+    let note_grid = match &args.note_grid_file {
+        Some(path) => load_note_grid(path).map_err(AppError::from)?,
+        None => args.note_grid_layout.grid(),
+    };
+    let layout_bank_notes = args.note_grid_layout.bank_notes();
+    let bank_notes = BankNotes {
+        lfo: BankNoteRange {
+            base: args
+                .lfo_bank_note_base
+                .unwrap_or(layout_bank_notes.lfo.base),
+            count: args.lfo_bank_note_count,
+        },
+        effect: BankNoteRange {
+            base: args
+                .effect_bank_note_base
+                .unwrap_or(layout_bank_notes.effect.base),
+            count: args.effect_bank_note_count,
+        },
+    };
+    validate_bank_notes(&bank_notes).map_err(AppError::from)?;
+    // A pad sharing a bank button's note would shadow that bank
+    if let Some(note) = note_grid
+        .iter()
+        .flatten()
+        .find(|&&note| bank_notes.lfo.contains(note) || bank_notes.effect.contains(note))
+    {
+        return Err(AppError::from(format!(
+            "Note grid pad note {} is also a bank select note; move the grid or set \
+             --lfo-bank-note-base and --effect-bank-note-base so they don't overlap",
+            note
+        )));
+    }
+    NOTE_GRID
+        .set(note_grid)
+        .map_err(|_| AppError::from("Note grid was already initialized"))?;
+    BANK_NOTES
+        .set(bank_notes)
+        .map_err(|_| AppError::from("Bank notes were already initialized"))?;
+
     let app_state = Arc::new(AppState::new()); // Now Arc<AppState>
+    {
+        let mut column_modes = app_state.column_modes.write().unwrap();
+        for &effect in &args.additive_columns {
+            if !(1..=TOTAL_COLS).contains(&effect) {
+                return Err(AppError::from(format!(
+                    "--additive-columns effect {} out of range 1-{}",
+                    effect, TOTAL_COLS
+                )));
+            }
+            column_modes[effect - 1] = ColumnMode::Additive;
+        }
+    }
 
     let osc_in_addr_str = format!("{}:{}", args.in_host, args.in_port);
     let osc_out_addr_str = format!("{}:{}", args.out_host, args.out_port);
@@ -233,8 +973,25 @@ async fn main() -> Result<(), AppError> {
     let osc_in_addr: SocketAddr = osc_in_addr_str.parse().map_err(AppError::from)?;
 
     // Restore MIDI Output and LED update channel
-    let midi_out_conn_arc = match setup_midi_output() {
-        Ok(conn) => Arc::new(Mutex::new(conn)),
+    let midi_out_setup = if args.simulate {
+        Err("simulating".to_string())
+    } else {
+        setup_midi_output()
+    };
+    let midi_out_conn_arc = match midi_out_setup {
+        Ok(conn) => Some(Arc::new(Mutex::new(conn))),
+        Err(_) if args.simulate => {
+            info!("Simulating: no MIDI output, LED feedback is disabled");
+            None
+        }
+        Err(e) if args.replay.is_some() => {
+            // Replay doesn't need the physical APC
+            warn!(
+                "Failed to setup MIDI output: {}. LED feedback will be disabled during replay.",
+                e
+            );
+            None
+        }
         Err(e) => {
             error!(
                 "Failed to setup MIDI output: {}. LED feedback will be disabled.",
@@ -245,9 +1002,9 @@ async fn main() -> Result<(), AppError> {
             return Err(e.into());
         }
     };
-    let (led_tx, led_rx) = mpsc::channel::<LedUpdateRequest>(8);
+    let (led_tx, mut led_rx) = mpsc::channel::<LedUpdateRequest>(8);
 
-    {
+    if let Some(midi_out_conn_arc) = &midi_out_conn_arc {
         let mut initial_midi_out = midi_out_conn_arc.lock().unwrap();
         clear_all_leds(&mut initial_midi_out);
         // Initial _update_bank_select_leds and _refresh_grid_leds calls are removed from here.
@@ -255,17 +1012,81 @@ async fn main() -> Result<(), AppError> {
         info!("Hardware LEDs cleared. Initial state will be set by LED update task.");
     }
 
-    let osc_input_task = tokio::spawn(handle_osc_input(Arc::clone(&app_state), osc_in_addr));
+    if args.led_selftest {
+        match &midi_out_conn_arc {
+            Some(midi_out_conn_arc) => led_selftest(midi_out_conn_arc).await,
+            None => warn!("--led-selftest requested but no MIDI output is available"),
+        }
+    }
+
+    let recorder = match &args.record {
+        Some(path) => {
+            info!("Recording MIDI session to {}", path);
+            Some(MidiRecorder::create(path).map_err(AppError::from)?)
+        }
+        None => None,
+    };
 
-    let (midi_event_tx, midi_event_rx) = mpsc::channel(64);
-    let midi_input_setup_task = tokio::spawn(keep_midi_input_alive(midi_event_tx));
+    if !args.lfo_int_divisor.is_finite() || args.lfo_int_divisor == 0.0 {
+        return Err(AppError::from(format!(
+            "--lfo-int-divisor must be finite and non-zero, got {}",
+            args.lfo_int_divisor
+        )));
+    }
+
+    if args.osc_buf_size == 0 {
+        return Err(AppError::from("--osc-buf-size must be at least 1"));
+    }
 
-    let led_update_task_handle = tokio::spawn(led_update_loop(
-        led_rx,
-        Arc::clone(&midi_out_conn_arc),
+    let osc_error_report_addr: Option<SocketAddr> = args
+        .osc_error_report
+        .as_deref()
+        .map(|addr| addr.parse())
+        .transpose()
+        .map_err(AppError::from)?;
+    let osc_input_config = OscInputConfig {
+        buf_size: args.osc_buf_size,
+        int_divisor: args.lfo_int_divisor,
+        error_report_addr: osc_error_report_addr,
+        error_report_interval: Duration::from_millis(args.osc_error_report_interval_ms),
+        led_tx: led_tx.clone(),
+    };
+    let osc_input_task = tokio::spawn(handle_osc_input(
         Arc::clone(&app_state),
+        osc_in_addr,
+        osc_input_config,
     ));
 
+    let (midi_event_tx, midi_event_rx) = mpsc::channel(64);
+    let midi_input_setup_task = match args.replay.clone() {
+        Some(path) => tokio::spawn(replay_midi_session(path, midi_event_tx)),
+        None if args.simulate => tokio::spawn(simulate_midi_input(midi_event_tx)),
+        None => tokio::spawn(keep_midi_input_alive(midi_event_tx)),
+    };
+
+    if args.fader_feedback {
+        match &midi_out_conn_arc {
+            Some(midi_out_conn_arc) => {
+                tokio::spawn(fader_feedback_loop(
+                    Arc::clone(midi_out_conn_arc),
+                    Arc::clone(&app_state),
+                ));
+            }
+            None => warn!("--fader-feedback requested but no MIDI output is available"),
+        }
+    }
+
+    let led_update_task_handle = match midi_out_conn_arc {
+        Some(midi_out_conn_arc) => tokio::spawn(led_update_loop(
+            led_rx,
+            midi_out_conn_arc,
+            Arc::clone(&app_state),
+            args.bank_flash,
+        )),
+        // No hardware to drive; keep draining requests so senders don't see a closed channel
+        None => tokio::spawn(async move { while led_rx.recv().await.is_some() {} }),
+    };
+
     // Send initial refresh request to the LED update task
     if let Err(e) = led_tx.try_send(LedUpdateRequest::BothRefresh) {
         warn!(
@@ -278,11 +1099,37 @@ async fn main() -> Result<(), AppError> {
         Arc::clone(&app_state),
         midi_event_rx,
         led_tx.clone(),
+        recorder,
+        args.midi_silence_secs.map(Duration::from_secs),
+    ));
+    if args.osc_out_bind.is_some() && args.osc_out_transport == OscTransport::Tcp {
+        warn!("--osc-out-bind only applies to the UDP OSC output; ignoring it for TCP");
+    }
+    let osc_sender_config = OscSenderConfig {
+        log_bundles: args.log_osc_bundles,
+        output_min: args.output_min,
+        output_max: args.output_max,
+        fader_override_policy: args.fader_override_policy,
+        transport: args.osc_out_transport,
+        bind_addr: args
+            .osc_out_bind
+            .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0))),
+        effect_addresses,
+        timetag: args.osc_timetag,
+        timetag_offset: Duration::from_millis(args.osc_timetag_offset_ms),
+        idle_after_ticks: args.idle_after_ticks,
+    };
+    let osc_sender_task = tokio::spawn(osc_sender_loop(
+        Arc::clone(&app_state),
+        osc_out_addr,
+        osc_sender_config,
     ));
-    let osc_sender_task = tokio::spawn(osc_sender_loop(Arc::clone(&app_state), osc_out_addr));
 
     info!("OSC Input: {}", osc_in_addr);
-    info!("OSC Output: {}", osc_out_addr);
+    info!(
+        "OSC Output: {} ({:?})",
+        osc_out_addr, args.osc_out_transport
+    );
     info!("Control mapper running...");
 
     match tokio::try_join!(
@@ -308,64 +1155,306 @@ async fn main() -> Result<(), AppError> {
     Ok(())
 }
 
-fn process_osc_message(msg: OscMessage, app_state: &Arc<AppState>) {
+// Normalizes a numeric OSC argument into an LFO value. Integers are scaled by the divisor.
+fn lfo_value_from_osc(arg: &OscType, int_divisor: f32) -> Option<f32> {
+    match arg {
+        OscType::Float(value) => Some(*value),
+        OscType::Double(value) => Some(*value as f32),
+        OscType::Int(value) => Some(*value as f32 / int_divisor),
+        OscType::Long(value) => Some(*value as f32 / int_divisor),
+        _ => None,
+    }
+}
+
+// A whole-number OSC argument, e.g. a 1-based bank number
+fn osc_whole_number(arg: &OscType) -> Option<i64> {
+    match arg {
+        OscType::Int(n) => Some(*n as i64),
+        OscType::Long(n) => Some(*n),
+        OscType::Float(n) if n.fract() == 0.0 => Some(*n as i64),
+        _ => None,
+    }
+}
+
+// The LFO bank named by an optional 1-based argument, or the current one
+fn osc_lfo_bank_arg(arg: Option<&OscType>, app_state: &AppState) -> Result<usize, String> {
+    let Some(arg) = arg else {
+        return Ok(app_state.banks.current_lfo_bank.load(Ordering::SeqCst));
+    };
+    let bank =
+        osc_whole_number(arg).ok_or_else(|| format!("expected an LFO bank, got {:?}", arg))?;
+    if bank < 1 || bank > NUM_LFO_BANKS as i64 {
+        return Err(format!(
+            "LFO bank {} out of range 1-{}",
+            bank, NUM_LFO_BANKS
+        ));
+    }
+    Ok((bank - 1) as usize)
+}
+
+// Parses the 1-based effect number of an OSC address
+fn osc_effect_index(effect_str: &str) -> Result<usize, String> {
+    let effect = effect_str
+        .parse::<usize>()
+        .map_err(|_| format!("could not parse effect from '{}'", effect_str))?;
+    if !(1..=TOTAL_COLS).contains(&effect) {
+        return Err(format!("effect {} out of range 1-{}", effect, TOTAL_COLS));
+    }
+    Ok(effect - 1)
+}
+
+// Applies one incoming OSC message, returning the reason if it was rejected
+fn process_osc_message(
+    msg: &OscMessage,
+    app_state: &Arc<AppState>,
+    config: &OscInputConfig,
+) -> Result<(), String> {
     if msg.addr.starts_with("/lfo/") {
         if let Some(row_str) = msg.addr.split('/').last() {
-            if let Ok(lfo_source_on_grid) = row_str.parse::<usize>() {
-                // LFOs are by row, so lfo_source_on_grid (1-8) corresponds to a row.
-                if lfo_source_on_grid >= 1 && lfo_source_on_grid <= NUM_ROWS {
-                    // Check against NUM_ROWS
-                    if let Some(OscType::Float(value)) = msg.args.get(0) {
-                        let current_lfo_bank =
-                            app_state.banks.current_lfo_bank.load(Ordering::SeqCst);
-                        let actual_lfo_idx = current_lfo_bank * NUM_ROWS + (lfo_source_on_grid - 1); // Use NUM_ROWS for LFO from row
-
-                        let mut latest_lfo_values_guard =
-                            app_state.latest_lfo_values.write().unwrap();
-                        if actual_lfo_idx < latest_lfo_values_guard.len() {
-                            latest_lfo_values_guard[actual_lfo_idx] = *value;
-                        } else {
-                            warn!("actual_lfo_idx {} out of bounds for latest_lfo_values (len {}). OSC lfo_source_on_grid: {}", actual_lfo_idx, latest_lfo_values_guard.len(), lfo_source_on_grid);
-                        }
-                    } else {
-                        warn!(
-                            "LFO message did not contain a float argument: {:?}",
-                            msg.args
-                        );
-                    }
-                } else {
-                    warn!("LFO source on grid out of range: {}", lfo_source_on_grid);
-                }
+            let lfo_source_on_grid = row_str
+                .parse::<usize>()
+                .map_err(|_| format!("could not parse LFO row from '{}'", row_str))?;
+            // LFOs are by row, so lfo_source_on_grid (1-8) corresponds to a row.
+            if lfo_source_on_grid < 1 || lfo_source_on_grid > NUM_ROWS {
+                return Err(format!(
+                    "LFO source {} out of range 1-{}",
+                    lfo_source_on_grid, NUM_ROWS
+                ));
+            }
+            let value = msg
+                .args
+                .first()
+                .and_then(|arg| lfo_value_from_osc(arg, config.int_divisor))
+                .ok_or_else(|| format!("no numeric argument in {:?}", msg.args))?;
+
+            let current_lfo_bank = app_state.banks.current_lfo_bank.load(Ordering::SeqCst);
+            let actual_lfo_idx = current_lfo_bank * NUM_ROWS + (lfo_source_on_grid - 1); // Use NUM_ROWS for LFO from row
+
+            let mut latest_lfo_values_guard = app_state.latest_lfo_values.write().unwrap();
+            if actual_lfo_idx < latest_lfo_values_guard.len() {
+                latest_lfo_values_guard[actual_lfo_idx] = value;
             } else {
-                warn!("Could not parse LFO row from address: {}", msg.addr);
+                warn!("actual_lfo_idx {} out of bounds for latest_lfo_values (len {}). OSC lfo_source_on_grid: {}", actual_lfo_idx, latest_lfo_values_guard.len(), lfo_source_on_grid);
+            }
+        }
+    } else if let Some(effect_str) = msg.addr.strip_prefix("/column_mode/") {
+        let effect = osc_effect_index(effect_str)? + 1;
+        let mode = match msg.args.first() {
+            Some(OscType::String(name)) => {
+                ColumnMode::parse(name).ok_or_else(|| format!("unknown column mode '{}'", name))?
+            }
+            _ => return Err("expected \"exclusive\" or \"additive\"".to_string()),
+        };
+        app_state.column_modes.write().unwrap()[effect - 1] = mode;
+        info!("Effect {} column mode set to {:?}", effect, mode);
+    } else if let Some(bank_kind) = msg.addr.strip_prefix("/bank/") {
+        // /bank/lfo <n> or /bank/effect <n>, with n 1-based like the other OSC addresses
+        let (bank_name, bank_atomic, bank_count) = match bank_kind {
+            "lfo" => ("LFO", &app_state.banks.current_lfo_bank, NUM_LFO_BANKS),
+            "effect" => (
+                "Effect",
+                &app_state.banks.current_effect_bank,
+                NUM_EFFECT_BANKS,
+            ),
+            _ => return Err("unhandled address".to_string()),
+        };
+        let bank = msg
+            .args
+            .first()
+            .and_then(osc_whole_number)
+            .ok_or_else(|| format!("expected a bank number, got {:?}", msg.args))?;
+        if bank < 1 || bank > bank_count as i64 {
+            return Err(format!("bank {} out of range 1-{}", bank, bank_count));
+        }
+        let new_bank = (bank - 1) as usize;
+        bank_atomic.store(new_bank, Ordering::SeqCst);
+        info!("Switched to {} Bank {} via OSC", bank_name, new_bank);
+        if let Err(e) = config.led_tx.try_send(LedUpdateRequest::BothRefresh) {
+            warn!(
+                "Failed to send BothRefresh LED update request for OSC bank switch: {}",
+                e
+            );
+        }
+    } else if let Some(effect_str) = msg.addr.strip_prefix("/override/clear/") {
+        // /override/clear/<effect> [lfo_bank]: hand the effect back to its LFO mapping
+        let effect_idx = osc_effect_index(effect_str)?;
+        let lfo_bank = osc_lfo_bank_arg(msg.args.first(), app_state)?;
+        app_state.fader_override_active.write().unwrap()[lfo_bank][effect_idx] = false;
+        info!(
+            "Fader override on effect {} for LFO Bank {} cleared via OSC",
+            effect_idx + 1,
+            lfo_bank + 1
+        );
+        if let Err(e) = config
+            .led_tx
+            .try_send(LedUpdateRequest::FaderColumnRefresh {
+                actual_effect_idx: effect_idx,
+            })
+        {
+            warn!(
+                "Failed to send FaderColumnRefresh LED update request: {}",
+                e
+            );
+        }
+    } else if let Some(effect_str) = msg.addr.strip_prefix("/override/") {
+        // /override/<effect> <value> [lfo_bank]: pin the effect like a fader would
+        let effect_idx = osc_effect_index(effect_str)?;
+        let value = msg
+            .args
+            .first()
+            .and_then(|arg| lfo_value_from_osc(arg, config.int_divisor))
+            .ok_or_else(|| format!("no numeric argument in {:?}", msg.args))?;
+        let lfo_bank = osc_lfo_bank_arg(msg.args.get(1), app_state)?;
+        {
+            let mut fader_override_active_guard = app_state.fader_override_active.write().unwrap();
+            let mut fader_override_value_guard = app_state.fader_override_value.write().unwrap();
+            fader_override_active_guard[lfo_bank][effect_idx] = true;
+            fader_override_value_guard[lfo_bank][effect_idx] = value;
+        }
+        debug!(
+            "OSC override: Set val={} for effect {}, LFO Bank {}",
+            value,
+            effect_idx + 1,
+            lfo_bank + 1
+        );
+        if let Err(e) = config
+            .led_tx
+            .try_send(LedUpdateRequest::FaderColumnRefresh {
+                actual_effect_idx: effect_idx,
+            })
+        {
+            warn!(
+                "Failed to send FaderColumnRefresh LED update request: {}",
+                e
+            );
+        }
+    } else if let Some(action) = msg.addr.strip_prefix("/snapshot/") {
+        // /snapshot/save <name> or /snapshot/recall <name>
+        let name = match msg.args.first() {
+            Some(OscType::String(name)) if !name.is_empty() => name.clone(),
+            _ => return Err(format!("expected a snapshot name, got {:?}", msg.args)),
+        };
+        match action {
+            "save" => {
+                let snapshot = StateSnapshot::capture(app_state);
+                app_state
+                    .snapshots
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), snapshot);
+                info!("Saved snapshot '{}' via OSC", name);
             }
+            "recall" => {
+                let snapshot = app_state
+                    .snapshots
+                    .read()
+                    .unwrap()
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| format!("no snapshot named '{}'", name))?;
+                snapshot.restore(app_state);
+                info!("Recalled snapshot '{}' via OSC", name);
+                if let Err(e) = config.led_tx.try_send(LedUpdateRequest::BothRefresh) {
+                    warn!(
+                        "Failed to send BothRefresh LED update request for snapshot recall: {}",
+                        e
+                    );
+                }
+            }
+            _ => return Err("unhandled address".to_string()),
         }
+    } else if msg.addr == "/state/meters" {
+        app_state.meters_requested.store(true, Ordering::SeqCst);
+    } else if msg.addr == "/state/mapping" {
+        app_state.mapping_requested.store(true, Ordering::SeqCst);
     } else if msg.addr == "/_samplerate" {
         // known message, can ignore if not used
     } else {
-        warn!("Received unhandled OSC message: {:?}", msg);
+        return Err("unhandled address".to_string());
+    }
+    Ok(())
+}
+
+// Processes a message, logging and reporting it if it was rejected
+fn handle_osc_message(
+    msg: OscMessage,
+    src_addr: SocketAddr,
+    app_state: &Arc<AppState>,
+    config: &OscInputConfig,
+    error_reporter: &mut Option<OscErrorReporter>,
+) {
+    if let Err(reason) = process_osc_message(&msg, app_state, config) {
+        warn!(
+            "Rejected OSC message {} from {}: {} ({:?})",
+            msg.addr, src_addr, reason, msg.args
+        );
+        if let Some(reporter) = error_reporter {
+            reporter.record(&msg.addr, &reason);
+        }
     }
 }
 
 // --- OSC Input Handling ---
-async fn handle_osc_input(app_state: Arc<AppState>, addr: SocketAddr) -> Result<(), AppError> {
-    info!("Starting OSC input listener on {}", addr);
+// Bytes of a malformed packet shown in the decode error log
+const OSC_DECODE_ERROR_PREVIEW_BYTES: usize = 16;
+
+// Hex dump of the start of a packet, e.g. "2f 6c 66 6f ..."
+fn hex_preview(data: &[u8]) -> String {
+    let mut preview = data
+        .iter()
+        .take(OSC_DECODE_ERROR_PREVIEW_BYTES)
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if data.len() > OSC_DECODE_ERROR_PREVIEW_BYTES {
+        preview.push_str(" ...");
+    }
+    preview
+}
+
+async fn handle_osc_input(
+    app_state: Arc<AppState>,
+    addr: SocketAddr,
+    config: OscInputConfig,
+) -> Result<(), AppError> {
+    info!(
+        "Starting OSC input listener on {} (buffer size {} bytes)",
+        addr, config.buf_size
+    );
     let socket = UdpSocket::bind(addr).map_err(AppError::from)?;
     socket.set_nonblocking(true).map_err(AppError::from)?;
-    let mut buf = [0u8; OSC_BUF_SIZE];
+    let mut buf = vec![0u8; config.buf_size];
+    let mut decode_failures: u64 = 0;
+    let mut error_reporter = config
+        .error_report_addr
+        .map(|target| OscErrorReporter::new(target, config.error_report_interval));
+    if let Some(target) = config.error_report_addr {
+        info!("Reporting rejected OSC input to {}", target);
+    }
     loop {
+        if let Some(reporter) = error_reporter.as_mut() {
+            reporter.flush_if_due(&socket);
+        }
         match socket.recv_from(&mut buf) {
-            Ok((size, _src_addr)) => {
+            Ok((size, src_addr)) => {
+                // A datagram that fills the whole buffer was most likely cut off
+                let possibly_truncated = size >= buf.len();
                 match decode_udp(&buf[..size]) {
                     Ok((_remaining_buf, OscPacket::Message(msg))) => {
-                        process_osc_message(msg, &app_state);
+                        handle_osc_message(msg, src_addr, &app_state, &config, &mut error_reporter);
                     }
                     Ok((_remaining_buf, OscPacket::Bundle(bundle))) => {
                         // warn!("Received OSC Bundle, processing contents...");
                         for packet in bundle.content {
                             match packet {
                                 OscPacket::Message(msg) => {
-                                    process_osc_message(msg, &app_state);
+                                    handle_osc_message(
+                                        msg,
+                                        src_addr,
+                                        &app_state,
+                                        &config,
+                                        &mut error_reporter,
+                                    );
                                 }
                                 OscPacket::Bundle(inner_bundle) => {
                                     warn!(
@@ -376,8 +1465,25 @@ async fn handle_osc_input(app_state: Arc<AppState>, addr: SocketAddr) -> Result<
                             }
                         }
                     }
+                    Err(e) if possibly_truncated => {
+                        decode_failures += 1;
+                        warn!(
+                            "OSC packet from {} filled the {} byte buffer and was likely truncated ({}). Increase --osc-buf-size.",
+                            src_addr,
+                            buf.len(),
+                            e
+                        );
+                    }
                     Err(e) => {
-                        error!("Error decoding OSC packet: {}", e);
+                        decode_failures += 1;
+                        warn!(
+                            "Error decoding {} byte OSC packet from {}: {} (starts {}; {} decode failures so far)",
+                            size,
+                            src_addr,
+                            e,
+                            hex_preview(&buf[..size]),
+                            decode_failures
+                        );
                     }
                 }
             }
@@ -480,6 +1586,45 @@ fn clear_all_leds(midi_out_conn: &mut MidiOutputConnection) {
     }
 }
 
+// Delay between LEDs in the startup self-test sweep
+const LED_SELFTEST_STEP: Duration = Duration::from_millis(10);
+
+// Walks every grid and bank LED through each color and back to off, one LED at a time
+async fn led_selftest(midi_out_conn_arc: &Arc<Mutex<MidiOutputConnection>>) {
+    info!("Running LED self-test");
+    let mut led_state = LedState::new();
+    for velocity in [LED_GREEN, LED_RED, LED_ORANGE, LED_OFF] {
+        for r_vis in 0..NUM_ROWS {
+            for c_vis in 0..NUM_COLS {
+                led_state.send_grid_note_if_changed(
+                    &mut midi_out_conn_arc.lock().unwrap(),
+                    r_vis,
+                    c_vis,
+                    velocity,
+                );
+                tokio::time::sleep(LED_SELFTEST_STEP).await;
+            }
+        }
+        for bank_idx in 0..NUM_LFO_BANKS {
+            led_state.send_lfo_bank_note_if_changed(
+                &mut midi_out_conn_arc.lock().unwrap(),
+                bank_idx,
+                velocity,
+            );
+            tokio::time::sleep(LED_SELFTEST_STEP).await;
+        }
+        for bank_idx in 0..NUM_EFFECT_BANKS {
+            led_state.send_effect_bank_note_if_changed(
+                &mut midi_out_conn_arc.lock().unwrap(),
+                bank_idx,
+                velocity,
+            );
+            tokio::time::sleep(LED_SELFTEST_STEP).await;
+        }
+    }
+    info!("LED self-test complete");
+}
+
 fn _update_bank_select_leds(
     midi_out_conn: &mut MidiOutputConnection,
     app_state: &Arc<AppState>,
@@ -554,13 +1699,63 @@ async fn process_midi_messages(
     app_state: Arc<AppState>,
     mut midi_rx: mpsc::Receiver<Vec<u8>>,
     led_tx: mpsc::Sender<LedUpdateRequest>,
+    mut recorder: Option<MidiRecorder>,
+    silence_limit: Option<Duration>,
 ) -> Result<(), AppError> {
     info!("Starting MIDI message processing task.");
-    while let Some(message_data) = midi_rx.recv().await {
+    // When the silence watchdog fires next; None while it is disabled or has already fired,
+    // until input arrives again
+    let mut silence_deadline = silence_limit.map(|limit| tokio::time::Instant::now() + limit);
+    let mut silent = false;
+    let mut record_flush_ticks = interval(RECORD_FLUSH_INTERVAL);
+    loop {
+        let silence = async {
+            match silence_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let received = tokio::select! {
+            received = midi_rx.recv() => received,
+            _ = silence => {
+                warn!(
+                    "No MIDI input for {:?}; the APC may have reset, resending all LEDs",
+                    silence_limit.unwrap_or_default()
+                );
+                if let Err(e) = led_tx.try_send(LedUpdateRequest::ResyncRefresh) {
+                    warn!("Failed to send ResyncRefresh LED update request: {}", e);
+                }
+                silence_deadline = None;
+                silent = true;
+                continue;
+            }
+            _ = record_flush_ticks.tick(), if recorder.is_some() => {
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.flush();
+                }
+                continue;
+            }
+        };
+        let Some(message_data) = received else {
+            break;
+        };
+        silence_deadline = silence_limit.map(|limit| tokio::time::Instant::now() + limit);
+        if silent {
+            info!("MIDI input resumed");
+            silent = false;
+        }
         if message_data.is_empty() {
             continue;
         }
         let status = message_data[0];
+
+        // Only note-ons and control changes alter state, so only those are recorded
+        if let Some(recorder) = recorder.as_mut() {
+            let is_note_on = status & 0xF0 == 0x90 && message_data.get(2).is_some_and(|&v| v > 0);
+            if is_note_on || status & 0xF0 == 0xB0 {
+                recorder.record(&message_data);
+            }
+        }
         let data1 = if message_data.len() > 1 {
             message_data[1]
         } else {
@@ -578,49 +1773,48 @@ async fn process_midi_messages(
             let velocity = data2;
             if velocity > 0 {
                 // True note-on
-                if (82..=85).contains(&note) {
+                let grid_position = note_grid_position(note);
+                // Grid pads take priority over bank buttons on the same note
+                let lfo_bank_button = bank_notes()
+                    .lfo
+                    .bank(note)
+                    .filter(|_| grid_position.is_none());
+                let effect_bank_button = bank_notes()
+                    .effect
+                    .bank(note)
+                    .filter(|_| grid_position.is_none());
+                if let Some(new_lfo_bank) = lfo_bank_button {
                     // LFO Bank
-                    let new_lfo_bank = (note - 82) as usize;
                     app_state
                         .banks
                         .current_lfo_bank
                         .store(new_lfo_bank, Ordering::SeqCst);
                     info!("Switched to LFO Bank {}", new_lfo_bank);
-                    if let Err(e) = led_tx.try_send(LedUpdateRequest::BothRefresh) {
+                    if let Err(e) = led_tx.try_send(LedUpdateRequest::BankSwitchRefresh {
+                        note,
+                        velocity: LED_ORANGE,
+                    }) {
                         warn!(
-                            "Failed to send BothRefresh LED update request for LFO bank switch: {}",
+                            "Failed to send BankSwitchRefresh LED update request for LFO bank switch: {}",
                             e
                         );
                     }
-                } else if (86..=89).contains(&note) {
+                } else if let Some(new_effect_bank) = effect_bank_button {
                     // Effect Bank
-                    let new_effect_bank = (note - 86) as usize;
                     app_state
                         .banks
                         .current_effect_bank
                         .store(new_effect_bank, Ordering::SeqCst);
                     info!("Switched to Effect Bank {}", new_effect_bank);
-                    if let Err(e) = led_tx.try_send(LedUpdateRequest::BothRefresh) {
-                        warn!("Failed to send BothRefresh LED update request for effect bank switch: {}", e);
+                    if let Err(e) = led_tx.try_send(LedUpdateRequest::BankSwitchRefresh {
+                        note,
+                        velocity: LED_BLUE_ISH,
+                    }) {
+                        warn!("Failed to send BankSwitchRefresh LED update request for effect bank switch: {}", e);
                     }
                 } else {
                     // Grid button
-                    let mut r_pressed_vis: Option<usize> = None;
-                    let mut c_pressed_vis: Option<usize> = None;
-                    for r_vis in 0..NUM_ROWS {
-                        for c_vis_inner in 0..NUM_COLS {
-                            if NOTE_GRID[r_vis][c_vis_inner] == note {
-                                r_pressed_vis = Some(r_vis);
-                                c_pressed_vis = Some(c_vis_inner);
-                                break;
-                            }
-                        }
-                        if r_pressed_vis.is_some() {
-                            break;
-                        }
-                    }
-
-                    if let (Some(r_pv), Some(c_pv)) = (r_pressed_vis, c_pressed_vis) {
+                    if let Some((r_pv, c_pv)) = grid_position {
                         let current_lfo_bank =
                             app_state.banks.current_lfo_bank.load(Ordering::SeqCst);
                         let current_effect_bank =
@@ -650,6 +1844,15 @@ async fn process_midi_messages(
                                     "Toggled OFF mapping: LFO {} to Effect {}",
                                     actual_r_lfo_idx, actual_c_effect_idx
                                 );
+                            } else if app_state.column_modes.read().unwrap()[actual_c_effect_idx]
+                                == ColumnMode::Additive
+                            {
+                                // Additive column: other LFOs stay mapped alongside this one
+                                mapping_guard[actual_r_lfo_idx][actual_c_effect_idx] = true;
+                                debug!(
+                                    "Toggled ON additive mapping: LFO {} to Effect {}",
+                                    actual_r_lfo_idx, actual_c_effect_idx
+                                );
                             } else {
                                 debug!("Attempting to map LFO {} to Effect {}. Applying mutual exclusivity...", actual_r_lfo_idx, actual_c_effect_idx);
                                 // Mutual exclusivity: An Effect (from visual column) can only be driven by one LFO (from visual row).
@@ -678,6 +1881,7 @@ async fn process_midi_messages(
                                     actual_r_lfo_idx, actual_c_effect_idx
                                 );
                             }
+                            app_state.refresh_lfo_drivers(&mapping_guard, actual_c_effect_idx);
                             // Grid button presses should always trigger a full refresh of the grid LEDs for the current view
                             if let Err(e) = led_tx.try_send(LedUpdateRequest::FullRefresh) {
                                 warn!("Failed to send FullRefresh LED update request for grid button: {}", e);
@@ -747,34 +1951,74 @@ async fn led_update_loop(
     mut led_rx: mpsc::Receiver<LedUpdateRequest>,
     midi_out_conn_arc: Arc<Mutex<MidiOutputConnection>>,
     app_state: Arc<AppState>,
+    bank_flash: bool,
 ) {
     info!("Starting LED update loop with diffing.");
     let mut led_state = LedState::new(); // Initialize LedState
 
     while let Some(request) = led_rx.recv().await {
         debug!("LED Update Task: Received {:?}", request);
-        let mut midi_out_guard = midi_out_conn_arc.lock().unwrap();
-        match request {
-            LedUpdateRequest::FullRefresh => {
-                _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
-            }
-            LedUpdateRequest::BothRefresh => {
-                _update_bank_select_leds(&mut midi_out_guard, &app_state, &mut led_state);
-                _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
-            }
-            LedUpdateRequest::FaderColumnRefresh { actual_effect_idx } => {
-                _refresh_fader_column_leds(
-                    &mut midi_out_guard,
-                    &app_state,
-                    actual_effect_idx,
-                    &mut led_state,
-                );
+        let flash_note = {
+            let mut midi_out_guard = midi_out_conn_arc.lock().unwrap();
+            match request {
+                LedUpdateRequest::FullRefresh => {
+                    _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    None
+                }
+                LedUpdateRequest::BothRefresh => {
+                    _update_bank_select_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    None
+                }
+                LedUpdateRequest::ResyncRefresh => {
+                    led_state.forget();
+                    _update_bank_select_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    None
+                }
+                LedUpdateRequest::BankSwitchRefresh { note, velocity } => {
+                    _update_bank_select_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    _refresh_grid_leds(&mut midi_out_guard, &app_state, &mut led_state);
+                    bank_flash.then_some((note, velocity))
+                }
+                LedUpdateRequest::FaderColumnRefresh { actual_effect_idx } => {
+                    _refresh_fader_column_leds(
+                        &mut midi_out_guard,
+                        &app_state,
+                        actual_effect_idx,
+                        &mut led_state,
+                    );
+                    None
+                }
             }
+        }; // MIDI lock released before any flash delays
+
+        if let Some((note, velocity)) = flash_note {
+            flash_bank_led(&midi_out_conn_arc, note, velocity).await;
         }
     }
     info!("LED update loop ended.");
 }
 
+// Confirmation flash for a bank button: off -> on -> off -> on over ~150ms.
+// Ends on `velocity`, which is what LedState already recorded for this note.
+async fn flash_bank_led(
+    midi_out_conn_arc: &Arc<Mutex<MidiOutputConnection>>,
+    note: u8,
+    velocity: u8,
+) {
+    for step_velocity in [LED_OFF, velocity, LED_OFF, velocity] {
+        if let Err(e) = midi_out_conn_arc
+            .lock()
+            .unwrap()
+            .send(&[0x90, note, step_velocity])
+        {
+            warn!("Failed to send MIDI note {} (bank flash): {}", note, e);
+        }
+        tokio::time::sleep(Duration::from_millis(37)).await;
+    }
+}
+
 // Helper function to refresh LEDs for a single fader's column (which is an Effect column)
 fn _refresh_fader_column_leds(
     midi_out_conn: &mut MidiOutputConnection,
@@ -824,22 +2068,274 @@ fn _refresh_fader_column_leds(
     }
 }
 
+// --- Motorized Fader Feedback ---
+// For each visible fader whose column is driven by an LFO (no fader override), send the
+// LFO's value back as CC so the motorized fader tracks it.
+async fn fader_feedback_loop(
+    midi_out_conn_arc: Arc<Mutex<MidiOutputConnection>>,
+    app_state: Arc<AppState>,
+) {
+    info!("Starting motorized fader feedback loop.");
+    let mut interval = interval(Duration::from_millis(33)); // ~30 Hz is plenty for motor faders
+    let mut last_sent_cc: [Option<u8>; NUM_COLS] = [None; NUM_COLS];
+
+    loop {
+        interval.tick().await;
+
+        let mut pending: Vec<(usize, u8)> = Vec::new();
+        {
+            let mapping_guard = app_state.mapping.read().unwrap();
+            let fader_override_active_guard = app_state.fader_override_active.read().unwrap();
+            let latest_lfo_values_guard = app_state.latest_lfo_values.read().unwrap();
+            let current_lfo_bank = app_state.banks.current_lfo_bank.load(Ordering::SeqCst);
+            let current_effect_bank = app_state.banks.current_effect_bank.load(Ordering::SeqCst);
+
+            for (c_vis, last_sent) in last_sent_cc.iter_mut().enumerate() {
+                let actual_effect_idx = current_effect_bank * NUM_COLS + c_vis;
+                if actual_effect_idx >= TOTAL_COLS {
+                    continue;
+                }
+
+                // The fader itself is the driver; leave it where the user put it
+                if (0..NUM_LFO_BANKS)
+                    .any(|bank| fader_override_active_guard[bank][actual_effect_idx])
+                {
+                    *last_sent = None;
+                    continue;
+                }
+
+                // Same priority as the OSC sender: highest visual row in the active LFO bank wins
+                let lfo_value = (0..NUM_ROWS).rev().find_map(|r_vis| {
+                    let actual_lfo_idx = current_lfo_bank * NUM_ROWS + r_vis;
+                    if actual_lfo_idx < TOTAL_ROWS
+                        && mapping_guard[actual_lfo_idx][actual_effect_idx]
+                    {
+                        latest_lfo_values_guard.get(actual_lfo_idx).copied()
+                    } else {
+                        None
+                    }
+                });
+
+                if let Some(value) = lfo_value {
+                    let cc_value = (value.clamp(0.0, 1.0) * 127.0).round() as u8;
+                    if *last_sent != Some(cc_value) {
+                        pending.push((c_vis, cc_value));
+                    }
+                }
+            }
+        } // All read locks are released here
+
+        if pending.is_empty() {
+            continue;
+        }
+
+        let mut midi_out_guard = midi_out_conn_arc.lock().unwrap();
+        for (c_vis, cc_value) in pending {
+            let cc_number = 48 + c_vis as u8;
+            if let Err(e) = midi_out_guard.send(&[0xB0, cc_number, cc_value]) {
+                warn!("Failed to send fader feedback CC {}: {}", cc_number, e);
+            } else {
+                last_sent_cc[c_vis] = Some(cc_value);
+            }
+        }
+    }
+}
+
+// --- OSC Output ---
+const OSC_TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const OSC_TCP_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+// A peer that doesn't take a frame within this long is treated as gone
+const OSC_TCP_WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+// Frames waiting for the TCP writer task; further frames are dropped until it catches up
+const OSC_TCP_QUEUE_FRAMES: usize = 8;
+
+// Destination for encoded OSC packets
+enum OscOutput {
+    Udp {
+        socket: UdpSocket,
+        target_addr: SocketAddr,
+    },
+    // OSC 1.0 stream framing: every packet is prefixed with its size as a big-endian int32.
+    // Connecting and writing happen in osc_tcp_writer so a slow peer never stalls the sender loop.
+    Tcp {
+        frames: mpsc::Sender<Vec<u8>>,
+        connected: Arc<AtomicBool>,
+        reconnected: Arc<AtomicBool>,
+    },
+    Discard,
+}
+
+impl OscOutput {
+    fn new(
+        transport: OscTransport,
+        target_addr: SocketAddr,
+        bind_addr: SocketAddr,
+    ) -> std::io::Result<Self> {
+        Ok(match transport {
+            OscTransport::Udp => {
+                let socket = UdpSocket::bind(bind_addr)?;
+                info!("OSC UDP output bound to {}", socket.local_addr()?);
+                OscOutput::Udp {
+                    socket,
+                    target_addr,
+                }
+            }
+            OscTransport::Tcp => {
+                let (frames, frames_rx) = mpsc::channel(OSC_TCP_QUEUE_FRAMES);
+                let connected = Arc::new(AtomicBool::new(false));
+                let reconnected = Arc::new(AtomicBool::new(false));
+                tokio::spawn(osc_tcp_writer(
+                    target_addr,
+                    frames_rx,
+                    connected.clone(),
+                    reconnected.clone(),
+                ));
+                OscOutput::Tcp {
+                    frames,
+                    connected,
+                    reconnected,
+                }
+            }
+            OscTransport::Discard => OscOutput::Discard,
+        })
+    }
+
+    // Fails with ErrorKind::NotConnected while a TCP connection is down, and with
+    // ErrorKind::WouldBlock when the packet was dropped because the TCP writer is behind
+    async fn send(&mut self, packet: &[u8]) -> std::io::Result<()> {
+        match self {
+            OscOutput::Udp {
+                socket,
+                target_addr,
+            } => socket.send_to(packet, *target_addr).map(|_| ()),
+            OscOutput::Discard => Ok(()),
+            OscOutput::Tcp {
+                frames, connected, ..
+            } => {
+                if !connected.load(Ordering::SeqCst) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::NotConnected,
+                        "OSC TCP output is not connected",
+                    ));
+                }
+                let mut framed = Vec::with_capacity(4 + packet.len());
+                framed.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+                framed.extend_from_slice(packet);
+                frames.try_send(framed).map_err(|e| match e {
+                    mpsc::error::TrySendError::Full(_) => std::io::Error::new(
+                        std::io::ErrorKind::WouldBlock,
+                        "OSC TCP output is backed up, dropped packet",
+                    ),
+                    mpsc::error::TrySendError::Closed(_) => std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        "OSC TCP writer has stopped",
+                    ),
+                })
+            }
+        }
+    }
+
+    // True once after each new TCP connection, so the caller can resend its full state
+    fn take_reconnected(&mut self) -> bool {
+        match self {
+            OscOutput::Udp { .. } | OscOutput::Discard => false,
+            OscOutput::Tcp { reconnected, .. } => reconnected.swap(false, Ordering::SeqCst),
+        }
+    }
+}
+
+// Owns the OSC TCP connection: (re)connects to `target_addr` and writes queued frames until
+// the OscOutput is dropped
+async fn osc_tcp_writer(
+    target_addr: SocketAddr,
+    mut frames: mpsc::Receiver<Vec<u8>>,
+    connected: Arc<AtomicBool>,
+    reconnected: Arc<AtomicBool>,
+) {
+    // Only the first failure of a run of failed attempts is logged above debug
+    let mut connect_failing = false;
+    while !frames.is_closed() {
+        let mut stream =
+            match tokio::time::timeout(OSC_TCP_CONNECT_TIMEOUT, TcpStream::connect(target_addr))
+                .await
+            {
+                Ok(Ok(stream)) => stream,
+                result => {
+                    let reason = match result {
+                        Ok(Err(e)) => e.to_string(),
+                        _ => "timed out".to_string(),
+                    };
+                    if connect_failing {
+                        debug!("OSC TCP connect to {} failed: {}", target_addr, reason);
+                    } else {
+                        warn!("OSC TCP connect to {} failed: {}", target_addr, reason);
+                        connect_failing = true;
+                    }
+                    tokio::time::sleep(OSC_TCP_RECONNECT_INTERVAL).await;
+                    continue;
+                }
+            };
+        connect_failing = false;
+        if let Err(e) = stream.set_nodelay(true) {
+            warn!("Failed to set TCP_NODELAY on OSC output: {}", e);
+        }
+        info!("OSC TCP output connected to {}", target_addr);
+        // Anything still queued was meant for the previous connection
+        while frames.try_recv().is_ok() {}
+        connected.store(true, Ordering::SeqCst);
+        reconnected.store(true, Ordering::SeqCst);
+
+        while let Some(frame) = frames.recv().await {
+            match tokio::time::timeout(OSC_TCP_WRITE_TIMEOUT, stream.write_all(&frame)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    warn!("OSC TCP output to {} lost: {}", target_addr, e);
+                    break;
+                }
+                Err(_) => {
+                    warn!(
+                        "OSC TCP peer {} stopped reading for {:?}, reconnecting",
+                        target_addr, OSC_TCP_WRITE_TIMEOUT
+                    );
+                    break;
+                }
+            }
+        }
+        connected.store(false, Ordering::SeqCst);
+    }
+}
+
 // --- OSC Sender Loop ---
+const ACTIVE_TICK_PERIOD: Duration = Duration::from_millis(16); // 60 Hz
+const IDLE_TICK_PERIOD: Duration = Duration::from_millis(100); // 10 Hz
+
 async fn osc_sender_loop(
     app_state: Arc<AppState>,
     target_addr: SocketAddr,
+    config: OscSenderConfig,
 ) -> Result<(), AppError> {
     info!("Starting OSC sender loop for {}", target_addr);
-    let socket = UdpSocket::bind("0.0.0.0:0").map_err(AppError::from)?;
-    let mut interval = interval(Duration::from_millis(16)); // 60 Hz
+    let mut output =
+        OscOutput::new(config.transport, target_addr, config.bind_addr).map_err(AppError::from)?;
+    let mut tick_period = ACTIVE_TICK_PERIOD;
+    let mut interval = interval(tick_period);
+    // Consecutive ticks with nothing to send, for --idle-after-ticks
+    let mut unchanged_ticks: u32 = 0;
     let mut osc_sent_values = vec![-1.0f32; TOTAL_COLS];
+    // Columns already reported as overridden by several banks; cleared once the conflict goes away
+    let mut warned_override_conflicts = [false; TOTAL_COLS];
     loop {
         interval.tick().await;
+        // A fresh TCP peer has none of our state, so resend every effect value
+        if output.take_reconnected() {
+            osc_sent_values.fill(-1.0);
+        }
+        let tick_start = std::time::Instant::now();
         let mut next_osc_values_to_send = osc_sent_values.clone();
 
         {
             // Acquire all necessary read locks at the beginning of the scope
-            let mapping_guard = app_state.mapping.read().unwrap();
+            let lfo_drivers_guard = app_state.lfo_drivers.read().unwrap();
             let fader_override_active_guard = app_state.fader_override_active.read().unwrap();
             let fader_override_value_guard = app_state.fader_override_value.read().unwrap();
             let latest_lfo_values_guard = app_state.latest_lfo_values.read().unwrap();
@@ -847,48 +2343,64 @@ async fn osc_sender_loop(
             let active_lfo_bank = app_state.banks.current_lfo_bank.load(Ordering::SeqCst);
 
             for actual_col_idx_effect in 0..TOTAL_COLS {
-                let mut found_active_driver_for_col = false;
+                // PRIORITY 1: Fader overrides from any LFO bank
+                let overriding_banks: Vec<usize> = (0..NUM_LFO_BANKS)
+                    .filter(|&bank| fader_override_active_guard[bank][actual_col_idx_effect])
+                    .collect();
 
-                for lfo_bank_idx_for_fader_check in 0..NUM_LFO_BANKS {
-                    if fader_override_active_guard[lfo_bank_idx_for_fader_check]
-                        [actual_col_idx_effect]
-                    {
-                        next_osc_values_to_send[actual_col_idx_effect] = fader_override_value_guard
-                            [lfo_bank_idx_for_fader_check][actual_col_idx_effect];
-                        found_active_driver_for_col = true;
-                        break;
+                if overriding_banks.len() > 1 {
+                    if !warned_override_conflicts[actual_col_idx_effect] {
+                        warn!(
+                            "Effect {} has fader overrides in LFO banks {:?}; resolving with {:?}",
+                            actual_col_idx_effect + 1,
+                            overriding_banks.iter().map(|b| b + 1).collect::<Vec<_>>(),
+                            config.fader_override_policy
+                        );
+                        warned_override_conflicts[actual_col_idx_effect] = true;
                     }
+                } else {
+                    warned_override_conflicts[actual_col_idx_effect] = false;
                 }
-                if found_active_driver_for_col {
+
+                if let Some(winning_bank) = config
+                    .fader_override_policy
+                    .select(&overriding_banks, active_lfo_bank)
+                {
+                    next_osc_values_to_send[actual_col_idx_effect] = fader_override_value_guard
+                        [winning_bank][actual_col_idx_effect]
+                        .clamp(config.output_min, config.output_max);
                     continue;
                 }
 
                 // PRIORITY 2: LFO Mappings (if no fader override for this actual_col_idx_effect)
-                // Search LFOs only within the currently active LFO bank.
-                // Iterate visual LFO rows (0 to NUM_ROWS-1) in the active bank, from highest visual row to lowest.
-                for visual_row_idx_lfo in (0..NUM_ROWS).rev() {
-                    let actual_row_idx_lfo = active_lfo_bank * NUM_ROWS + visual_row_idx_lfo;
-
-                    if actual_row_idx_lfo < TOTAL_ROWS {
-                        // Ensure global LFO index is within bounds of mapping array
-                        if mapping_guard[actual_row_idx_lfo][actual_col_idx_effect] {
-                            if actual_row_idx_lfo < latest_lfo_values_guard.len() {
-                                let lfo_val = latest_lfo_values_guard[actual_row_idx_lfo];
-                                next_osc_values_to_send[actual_col_idx_effect] = lfo_val;
-                            }
-                            break;
-                        }
+                // The driver is the highest mapped visual row of the active LFO bank, kept
+                // up to date by process_midi_messages.
+                if let Some(actual_row_idx_lfo) =
+                    lfo_drivers_guard[active_lfo_bank][actual_col_idx_effect]
+                {
+                    if actual_row_idx_lfo < latest_lfo_values_guard.len() {
+                        // Clamp so a misbehaving LFO source can't push the engine out of range
+                        let lfo_val = latest_lfo_values_guard[actual_row_idx_lfo]
+                            .clamp(config.output_min, config.output_max);
+                        next_osc_values_to_send[actual_col_idx_effect] = lfo_val;
                     }
                 }
             }
         } // All read locks are released here
+        let tick_work = tick_start.elapsed();
+        if tick_work > tick_period {
+            debug!(
+                "OSC sender tick took {:?}, longer than its {:?} period",
+                tick_work, tick_period
+            );
+        }
 
         let mut messages_for_bundle: Vec<OscPacket> = Vec::new();
         let mut indices_updated_in_bundle: Vec<usize> = Vec::new();
 
         for i in 0..TOTAL_COLS {
             if (next_osc_values_to_send[i] - osc_sent_values[i]).abs() > f32::EPSILON {
-                let msg_addr = format!("/effect/{}", i + 1);
+                let msg_addr = config.effect_addresses[i].clone();
                 let msg_args = vec![OscType::Float(next_osc_values_to_send[i])];
                 messages_for_bundle.push(OscPacket::Message(OscMessage {
                     addr: msg_addr,
@@ -898,25 +2410,63 @@ async fn osc_sender_loop(
             }
         }
 
+        if let Some(idle_after_ticks) = config.idle_after_ticks {
+            let next_period = if messages_for_bundle.is_empty() {
+                unchanged_ticks = unchanged_ticks.saturating_add(1);
+                if unchanged_ticks >= idle_after_ticks {
+                    IDLE_TICK_PERIOD
+                } else {
+                    ACTIVE_TICK_PERIOD
+                }
+            } else {
+                unchanged_ticks = 0;
+                ACTIVE_TICK_PERIOD
+            };
+            if next_period != tick_period {
+                debug!("OSC sender interval now {:?}", next_period);
+                tick_period = next_period;
+                interval = tokio::time::interval_at(
+                    tokio::time::Instant::now() + tick_period,
+                    tick_period,
+                );
+            }
+        }
+
         if !messages_for_bundle.is_empty() {
             let bundle = OscPacket::Bundle(rosc::OscBundle {
-                timetag: rosc::OscTime {
-                    seconds: 0,
-                    fractional: 1,
-                }, // Represents "immediately"
+                timetag: config.bundle_timetag(),
                 content: messages_for_bundle,
             });
             match encoder::encode(&bundle) {
                 Ok(encoded_bundle) => {
-                    if let Err(e) = socket.send_to(&encoded_bundle, target_addr) {
-                        error!("Failed to send OSC bundle: {}", e);
+                    if let Err(e) = output.send(&encoded_bundle).await {
+                        // Unsent values stay pending and are retried on the next tick
+                        if matches!(
+                            e.kind(),
+                            std::io::ErrorKind::NotConnected | std::io::ErrorKind::WouldBlock
+                        ) {
+                            debug!("Skipped OSC bundle: {}", e);
+                        } else {
+                            error!("Failed to send OSC bundle: {}", e);
+                        }
                     } else {
                         // If send was successful (or at least, no immediate error),
                         // update the sent values for the included messages.
                         for &idx in &indices_updated_in_bundle {
                             osc_sent_values[idx] = next_osc_values_to_send[idx];
                         }
-                        // tracing::debug!("Sent OSC bundle with {} messages", indices_updated_in_bundle.len());
+                        if config.log_bundles {
+                            // Effect numbers are 1-based to match the default /effect/N addresses
+                            let effects: Vec<(usize, f32)> = indices_updated_in_bundle
+                                .iter()
+                                .map(|&idx| (idx + 1, next_osc_values_to_send[idx]))
+                                .collect();
+                            debug!(
+                                count = effects.len(),
+                                effects = ?effects,
+                                "Sent OSC bundle"
+                            );
+                        }
                     }
                 }
                 Err(e) => {
@@ -924,5 +2474,144 @@ async fn osc_sender_loop(
                 }
             }
         }
+
+        if app_state.meters_requested.swap(false, Ordering::SeqCst) {
+            send_meters_bundle(&mut output, &app_state, &osc_sent_values, &config).await;
+        }
+        if app_state.mapping_requested.swap(false, Ordering::SeqCst) {
+            send_mapping_snapshot(&mut output, &app_state).await;
+        }
+    }
+}
+
+// Reply to a /state/meters query with the current LFO inputs and last-sent effect values.
+// Effects that have never been sent are reported as -1.0.
+async fn send_meters_bundle(
+    output: &mut OscOutput,
+    app_state: &Arc<AppState>,
+    osc_sent_values: &[f32],
+    config: &OscSenderConfig,
+) {
+    let lfo_args: Vec<OscType> = app_state
+        .latest_lfo_values
+        .read()
+        .unwrap()
+        .iter()
+        .map(|&v| OscType::Float(v))
+        .collect();
+    let effect_args: Vec<OscType> = osc_sent_values.iter().map(|&v| OscType::Float(v)).collect();
+
+    let bundle = OscPacket::Bundle(rosc::OscBundle {
+        timetag: config.bundle_timetag(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/state/meters/lfo".to_string(),
+                args: lfo_args,
+            }),
+            OscPacket::Message(OscMessage {
+                addr: "/state/meters/effects".to_string(),
+                args: effect_args,
+            }),
+        ],
+    });
+    match encoder::encode(&bundle) {
+        Ok(encoded_bundle) => {
+            if let Err(e) = output.send(&encoded_bundle).await {
+                error!("Failed to send meters bundle: {}", e);
+            }
+        }
+        Err(e) => {
+            error!("Failed to encode meters bundle: {}", e);
+        }
+    }
+}
+
+// Routing state reported by a /state/mapping query
+#[derive(Debug, Clone, Serialize)]
+struct MappingSnapshot {
+    current_lfo_bank: usize,
+    current_effect_bank: usize,
+    // mapping[lfo][effect] across all banks
+    mapping: Vec<Vec<bool>>,
+    // fader_override_*[lfo_bank][effect]
+    fader_override_active: Vec<Vec<bool>>,
+    fader_override_value: Vec<Vec<f32>>,
+}
+
+impl MappingSnapshot {
+    fn capture(app_state: &AppState) -> Self {
+        MappingSnapshot {
+            current_lfo_bank: app_state.banks.current_lfo_bank.load(Ordering::SeqCst),
+            current_effect_bank: app_state.banks.current_effect_bank.load(Ordering::SeqCst),
+            mapping: app_state.mapping.read().unwrap().clone(),
+            fader_override_active: app_state.fader_override_active.read().unwrap().clone(),
+            fader_override_value: app_state.fader_override_value.read().unwrap().clone(),
+        }
+    }
+}
+
+// The whole mapper state, kept by /snapshot/save so /snapshot/recall can switch scenes at once
+#[derive(Debug, Clone)]
+struct StateSnapshot {
+    routing: MappingSnapshot,
+    column_modes: Vec<ColumnMode>,
+    latest_lfo_values: Vec<f32>,
+}
+
+impl StateSnapshot {
+    fn capture(app_state: &AppState) -> Self {
+        StateSnapshot {
+            routing: MappingSnapshot::capture(app_state),
+            column_modes: app_state.column_modes.read().unwrap().clone(),
+            latest_lfo_values: app_state.latest_lfo_values.read().unwrap().clone(),
+        }
+    }
+
+    fn restore(self, app_state: &AppState) {
+        let routing = self.routing;
+        {
+            let mut mapping = app_state.mapping.write().unwrap();
+            *mapping = routing.mapping;
+            for effect in 0..TOTAL_COLS {
+                app_state.refresh_lfo_drivers(&mapping, effect);
+            }
+        }
+        *app_state.fader_override_active.write().unwrap() = routing.fader_override_active;
+        *app_state.fader_override_value.write().unwrap() = routing.fader_override_value;
+        *app_state.column_modes.write().unwrap() = self.column_modes;
+        *app_state.latest_lfo_values.write().unwrap() = self.latest_lfo_values;
+        app_state
+            .banks
+            .current_lfo_bank
+            .store(routing.current_lfo_bank, Ordering::SeqCst);
+        app_state
+            .banks
+            .current_effect_bank
+            .store(routing.current_effect_bank, Ordering::SeqCst);
+    }
+}
+
+// Reply to a /state/mapping query with the routing state as a JSON string
+async fn send_mapping_snapshot(output: &mut OscOutput, app_state: &Arc<AppState>) {
+    let json = match serde_json::to_string(&MappingSnapshot::capture(app_state)) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize mapping snapshot: {}", e);
+            return;
+        }
+    };
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/state/mapping".to_string(),
+        args: vec![OscType::String(json)],
+    });
+    match encoder::encode(&packet) {
+        Ok(encoded) => {
+            if let Err(e) = output.send(&encoded).await {
+                error!("Failed to send mapping snapshot: {}", e);
+            }
+        }
+        Err(e) => {
+            error!("Failed to encode mapping snapshot: {}", e);
+        }
     }
 }
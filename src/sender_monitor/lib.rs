@@ -1,5 +1,6 @@
 use pyo3::prelude::*;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 // Re-export the sender_monitor module
@@ -9,6 +10,21 @@ pub mod web_monitor;
 use sender_monitor::SenderMonitor;
 use web_monitor::WebMonitor;
 
+// Report a failure in a background task through Python's logging module (logger
+// "sender_monitor_rs"), so the application can observe it with its own handlers; falls back to
+// stderr if logging can't be reached
+fn log_background_error(message: &str) {
+    let logged = Python::with_gil(|py| -> PyResult<()> {
+        py.import("logging")?
+            .call_method1("getLogger", ("sender_monitor_rs",))?
+            .call_method1("error", (message,))?;
+        Ok(())
+    });
+    if logged.is_err() {
+        eprintln!("{}", message);
+    }
+}
+
 #[pymodule]
 mod sender_monitor_rs {
     use super::*;
@@ -18,6 +34,36 @@ mod sender_monitor_rs {
         runtime: Arc<Runtime>,
         sender_monitor: Arc<SenderMonitor>,
         web_monitor: Option<Arc<WebMonitor>>,
+        web_monitor_port: Option<u16>, // Port the web monitor actually bound to
+    }
+
+    impl SenderMonitorManagerPy {
+        // Bind synchronously so bind errors reach the caller and the real port is known, then
+        // serve in the background
+        fn start_web_monitor_on(&mut self, web_monitor: WebMonitor, port: u16) -> PyResult<u16> {
+            let listener = self.runtime.block_on(web_monitor.bind(port)).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to bind sender monitor web server on port {}: {}",
+                    port, e
+                ))
+            })?;
+            let bound_port = listener
+                .local_addr()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+                .port();
+
+            let web_monitor = Arc::new(web_monitor);
+            let web_monitor_clone = web_monitor.clone();
+            self.runtime.spawn(async move {
+                if let Err(e) = web_monitor_clone.serve(listener).await {
+                    log_background_error(&format!("Sender monitor web server error: {}", e));
+                }
+            });
+
+            self.web_monitor = Some(web_monitor);
+            self.web_monitor_port = Some(bound_port);
+            Ok(bound_port)
+        }
     }
 
     #[pymethods]
@@ -35,12 +81,38 @@ mod sender_monitor_rs {
                 runtime,
                 sender_monitor,
                 web_monitor: None,
+                web_monitor_port: None,
             })
         }
 
-        fn register_controller(&self, ip: String, port: u16) -> PyResult<()> {
-            self.sender_monitor.register_controller(ip, port);
-            Ok(())
+        // Returns False if the controller was already registered (its stats are kept)
+        #[pyo3(signature = (ip, port, group=None))]
+        fn register_controller(
+            &self,
+            ip: String,
+            port: u16,
+            group: Option<String>,
+        ) -> PyResult<bool> {
+            Ok(self
+                .sender_monitor
+                .register_controller_with_group(ip, port, group))
+        }
+
+        #[pyo3(signature = (ip, port, group=None))]
+        fn set_controller_group(
+            &self,
+            ip: String,
+            port: u16,
+            group: Option<String>,
+        ) -> PyResult<()> {
+            if self.sender_monitor.set_controller_group(&ip, port, group) {
+                Ok(())
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "Controller {}:{} is not registered",
+                    ip, port
+                )))
+            }
         }
 
         fn set_cooldown_duration(&self, cooldown_seconds: i64) -> PyResult<()> {
@@ -51,10 +123,30 @@ mod sender_monitor_rs {
             Ok(())
         }
 
-        fn report_controller_success(&self, ip: String, port: u16) -> PyResult<()> {
+        // Mark controllers without a reported success or failure in this many seconds as not
+        // routable; None disables expiry
+        #[pyo3(signature = (ttl_seconds=None))]
+        fn set_activity_ttl(&self, ttl_seconds: Option<i64>) -> PyResult<()> {
             let sender_monitor = self.sender_monitor.clone();
             self.runtime.spawn(async move {
-                sender_monitor.report_controller_success(&ip, port).await;
+                sender_monitor.set_activity_ttl(ttl_seconds).await;
+            });
+            Ok(())
+        }
+
+        // `bytes` is how much this transaction sent, for per-controller throughput
+        #[pyo3(signature = (ip, port, bytes=None))]
+        fn report_controller_success(
+            &self,
+            ip: String,
+            port: u16,
+            bytes: Option<u64>,
+        ) -> PyResult<()> {
+            let sender_monitor = self.sender_monitor.clone();
+            self.runtime.spawn(async move {
+                sender_monitor
+                    .report_controller_success_with_bytes(&ip, port, bytes.unwrap_or(0))
+                    .await;
             });
             Ok(())
         }
@@ -74,6 +166,17 @@ mod sender_monitor_rs {
             Ok(())
         }
 
+        // Re-baseline FPS and frame interval stats, e.g. after loading new content
+        fn reset_frame_stats(&self) -> PyResult<()> {
+            self.sender_monitor.reset_frame_stats();
+            Ok(())
+        }
+
+        fn report_bytes(&self, bytes: u64) -> PyResult<()> {
+            self.sender_monitor.report_bytes(bytes);
+            Ok(())
+        }
+
         fn set_debug_mode(&self, enabled: bool) -> PyResult<()> {
             let sender_monitor = self.sender_monitor.clone();
             self.runtime.spawn(async move {
@@ -108,6 +211,42 @@ mod sender_monitor_rs {
             Ok(result)
         }
 
+        // Block until the pause state differs from `current` (or the timeout passes) and return
+        // the state then, so a render loop can sleep while paused instead of polling is_paused
+        #[pyo3(signature = (current, timeout_ms=None))]
+        fn wait_for_pause_change(
+            &self,
+            py: Python<'_>,
+            current: bool,
+            timeout_ms: Option<u64>,
+        ) -> PyResult<bool> {
+            let mut pause_rx = self.sender_monitor.subscribe_pause();
+            let runtime = self.runtime.clone();
+
+            let paused = py.allow_threads(move || {
+                runtime.block_on(async move {
+                    let changed = pause_rx.wait_for(|&paused| paused != current);
+                    match timeout_ms {
+                        Some(ms) => {
+                            let _ = tokio::time::timeout(Duration::from_millis(ms), changed).await;
+                        }
+                        None => {
+                            let _ = changed.await;
+                        }
+                    }
+                    let paused = *pause_rx.borrow();
+                    paused
+                })
+            });
+            Ok(paused)
+        }
+
+        // Increases whenever the debug command changes; compare against the last seen value to
+        // skip get_debug_command (and the dict it builds) when nothing changed
+        fn get_debug_command_version(&self) -> PyResult<u64> {
+            Ok(self.sender_monitor.get_debug_command_version())
+        }
+
         fn get_debug_command(&self) -> PyResult<Option<pyo3::PyObject>> {
             let sender_monitor = self.sender_monitor.clone();
             let runtime = self.runtime.clone();
@@ -126,6 +265,7 @@ mod sender_monitor_rs {
                             let mt_dict = pyo3::types::PyDict::new(py);
                             mt_dict.set_item("orientation", mt.orientation).unwrap();
                             mt_dict.set_item("layer", mt.layer).unwrap();
+                            mt_dict.set_item("all_layers", mt.all_layers).unwrap();
                             mt_dict.set_item("color", mt.color).unwrap();
                             mt_dict.set_item("target", mt.target).unwrap();
                             dict.set_item("mapping_tester", mt_dict).unwrap();
@@ -154,39 +294,25 @@ mod sender_monitor_rs {
             }
         }
 
-        fn start_web_monitor(&mut self, port: u16) -> PyResult<()> {
-            let sender_monitor = self.sender_monitor.clone();
-            let web_monitor = Arc::new(WebMonitor::new(sender_monitor));
-
-            let web_monitor_clone = web_monitor.clone();
-            self.runtime.spawn(async move {
-                if let Err(e) = web_monitor_clone.start_server(port).await {
-                    eprintln!("Sender monitor web server error: {}", e);
-                }
-            });
-
-            self.web_monitor = Some(web_monitor);
-            Ok(())
+        // Returns the bound port, which differs from `port` when port 0 is passed
+        fn start_web_monitor(&mut self, port: u16) -> PyResult<u16> {
+            let web_monitor = WebMonitor::new(self.sender_monitor.clone());
+            self.start_web_monitor_on(web_monitor, port)
         }
 
         fn start_web_monitor_with_bind_address(
             &mut self,
             port: u16,
             bind_address: String,
-        ) -> PyResult<()> {
-            let sender_monitor = self.sender_monitor.clone();
+        ) -> PyResult<u16> {
             let web_monitor =
-                Arc::new(WebMonitor::new(sender_monitor).with_bind_address(bind_address));
-
-            let web_monitor_clone = web_monitor.clone();
-            self.runtime.spawn(async move {
-                if let Err(e) = web_monitor_clone.start_server(port).await {
-                    eprintln!("Sender monitor web server error: {}", e);
-                }
-            });
+                WebMonitor::new(self.sender_monitor.clone()).with_bind_address(bind_address);
+            self.start_web_monitor_on(web_monitor, port)
+        }
 
-            self.web_monitor = Some(web_monitor);
-            Ok(())
+        // None until a web monitor has been started
+        fn get_web_monitor_port(&self) -> Option<u16> {
+            self.web_monitor_port
         }
 
         fn get_controller_count(&self) -> PyResult<usize> {
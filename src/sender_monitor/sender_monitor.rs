@@ -1,17 +1,52 @@
 use chrono::{DateTime, Duration, Utc};
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use std::cmp;
+use std::collections::VecDeque;
+use std::net::Ipv4Addr;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use tokio::sync::RwLock;
-
-// Helper function to parse IP address for proper sorting
-fn parse_ip_for_sorting(ip: &str) -> Vec<u8> {
-    ip.split('.')
-        .filter_map(|octet| octet.parse::<u8>().ok())
-        .collect()
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{broadcast, watch, RwLock};
+
+// Number of recent frame intervals kept for percentiles (~10s at 60fps)
+const FRAME_INTERVAL_HISTORY: usize = 600;
+// Number of most recent intervals included in stats for the dashboard sparkline
+const FRAME_INTERVAL_SPARKLINE: usize = 120;
+// Debug state snapshots buffered per event subscriber before it starts lagging
+const DEBUG_EVENT_CAPACITY: usize = 16;
+
+#[derive(Debug, Default)]
+struct FrameIntervals {
+    last_frame: Option<Instant>,
+    intervals_ms: VecDeque<f64>,
 }
 
+// Nearest-rank percentile of an ascending slice; 0.0 when empty
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+// Order controllers by IP then port. IPv4 addresses compare numerically and come first;
+// anything else (IPv6, hostnames) falls back to comparing the raw string.
+fn compare_controllers(a: &ControllerStatus, b: &ControllerStatus) -> cmp::Ordering {
+    let ip_order = match (a.ip.parse::<Ipv4Addr>(), b.ip.parse::<Ipv4Addr>()) {
+        (Ok(a_ip), Ok(b_ip)) => a_ip.cmp(&b_ip),
+        (Ok(_), Err(_)) => cmp::Ordering::Less,
+        (Err(_), Ok(_)) => cmp::Ordering::Greater,
+        (Err(_), Err(_)) => a.ip.cmp(&b.ip),
+    };
+    ip_order.then(a.port.cmp(&b.port))
+}
+
+// last_error of a controller expired by the activity TTL
+pub const NO_RECENT_ACTIVITY: &str = "No recent activity";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControllerStatus {
     pub ip: String,
@@ -23,6 +58,37 @@ pub struct ControllerStatus {
     pub failure_count: u64,
     pub last_error: Option<String>,
     pub cooldown_until: Option<DateTime<Utc>>, // Cooldown period after failure
+    #[serde(default)]
+    pub group: Option<String>, // Zone label like "floor" or "ceiling", for the dashboard
+    // Statuses without one count as just registered rather than idle since the epoch
+    #[serde(default = "Utc::now")]
+    pub registered_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>, // Last reported success or failure
+    #[serde(default)]
+    pub bytes_sent: u64, // Accumulated from successes reported with a byte count
+    #[serde(default)]
+    pub throughput_bps: f64, // Smoothed bytes per second
+    #[serde(skip)]
+    throughput_sample: Option<(u64, DateTime<Utc>)>, // (bytes_sent, time) at the last update
+}
+
+impl ControllerStatus {
+    // First-order low-pass filter with a 2 second time constant, matching the control port's
+    fn update_throughput(&mut self, now: DateTime<Utc>) {
+        match self.throughput_sample {
+            Some((last_bytes, last_time)) => {
+                let time_diff = (now - last_time).num_milliseconds() as f64 / 1000.0;
+                if time_diff > 0.1 {
+                    let instant_bps = self.bytes_sent.saturating_sub(last_bytes) as f64 / time_diff;
+                    let alpha = time_diff / (2.0 + time_diff);
+                    self.throughput_bps = alpha * instant_bps + (1.0 - alpha) * self.throughput_bps;
+                    self.throughput_sample = Some((self.bytes_sent, now));
+                }
+            }
+            None => self.throughput_sample = Some((self.bytes_sent, now)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +96,13 @@ pub struct SystemStats {
     pub fps: f64,
     pub uptime_seconds: f64,
     pub total_frames: u64,
+    pub total_bytes: u64,
+    pub bytes_per_second: f64, // Aggregate rate over the last sampling window (>= 1s)
+    // Percentiles of the time between report_frame calls, over the last FRAME_INTERVAL_HISTORY frames
+    pub frame_interval_p50_ms: f64,
+    pub frame_interval_p95_ms: f64,
+    pub frame_interval_p99_ms: f64,
+    pub recent_frame_intervals_ms: Vec<f64>,
     pub last_update: DateTime<Utc>,
 }
 
@@ -53,6 +126,22 @@ pub struct MappingTesterCommand {
     pub layer: usize,
     pub color: String,  // hex color like "#FF0000"
     pub target: String, // "world" or "cube_0", "cube_1", etc.
+    #[serde(default)]
+    pub all_layers: bool, // Light every layer in this orientation; `layer` is ignored
+}
+
+impl MappingTesterCommand {
+    // Number of layers along the axis normal to the plane, given (width, height, length).
+    // Returns None for an unknown orientation.
+    pub fn layer_count(&self, dimensions: (usize, usize, usize)) -> Option<usize> {
+        let (width, height, length) = dimensions;
+        match self.orientation.as_str() {
+            "xy" => Some(length), // Z-axis
+            "xz" => Some(height), // Y-axis
+            "yz" => Some(width),  // X-axis
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,11 +173,19 @@ pub struct SenderMonitor {
     system_stats: Arc<RwLock<SystemStats>>,
     start_time: DateTime<Utc>,
     frame_counter: AtomicU64,
+    fps_baseline: Mutex<(u64, DateTime<Utc>)>, // (total frames, time) FPS is measured from
+    byte_counter: AtomicU64,
+    bandwidth_sample: Arc<RwLock<(u64, DateTime<Utc>)>>, // (total bytes, time) at window start
+    frame_intervals: Mutex<FrameIntervals>,
     cooldown_duration: Arc<RwLock<Duration>>, // Duration of cooldown period
+    activity_ttl: Arc<RwLock<Option<Duration>>>, // Expire controllers not heard from within this
     debug_state: Arc<RwLock<DebugState>>,
+    pause_tx: watch::Sender<bool>, // Mirrors debug_state.is_paused for waiters
+    debug_events: broadcast::Sender<DebugState>, // Snapshot of debug_state after every change
     debug_command: Arc<RwLock<Option<DebugCommand>>>,
+    debug_command_version: AtomicU64, // Bumped on every set_debug_command
     world_dimensions: Arc<RwLock<Option<(usize, usize, usize)>>>, // (width, height, length)
-    cube_list: Arc<RwLock<Vec<CubeInfo>>>,                        // List of available cubes
+    cube_list: Arc<RwLock<Vec<CubeInfo>>>, // List of available cubes
 }
 
 impl SenderMonitor {
@@ -99,18 +196,32 @@ impl SenderMonitor {
                 fps: 0.0,
                 uptime_seconds: 0.0,
                 total_frames: 0,
+                total_bytes: 0,
+                bytes_per_second: 0.0,
+                frame_interval_p50_ms: 0.0,
+                frame_interval_p95_ms: 0.0,
+                frame_interval_p99_ms: 0.0,
+                recent_frame_intervals_ms: Vec::new(),
                 last_update: Utc::now(),
             })),
             start_time: Utc::now(),
             frame_counter: AtomicU64::new(0),
+            fps_baseline: Mutex::new((0, Utc::now())),
+            byte_counter: AtomicU64::new(0),
+            bandwidth_sample: Arc::new(RwLock::new((0, Utc::now()))),
+            frame_intervals: Mutex::new(FrameIntervals::default()),
             cooldown_duration: Arc::new(RwLock::new(Duration::seconds(30))), // 30 second cooldown by default
+            activity_ttl: Arc::new(RwLock::new(None)),
             debug_state: Arc::new(RwLock::new(DebugState {
                 is_debug_mode: false,
                 is_paused: false,
                 current_debug_command: None,
                 debug_data: serde_json::json!({}),
             })),
+            pause_tx: watch::Sender::new(false),
+            debug_events: broadcast::channel(DEBUG_EVENT_CAPACITY).0,
             debug_command: Arc::new(RwLock::new(None)),
+            debug_command_version: AtomicU64::new(0),
             world_dimensions: Arc::new(RwLock::new(None)),
             cube_list: Arc::new(RwLock::new(Vec::new())),
         }
@@ -126,7 +237,35 @@ impl SenderMonitor {
         *duration = Duration::seconds(cooldown_seconds);
     }
 
-    pub fn register_controller(&self, ip: String, port: u16) {
+    // None disables expiry
+    pub async fn set_activity_ttl(&self, ttl_seconds: Option<i64>) {
+        *self.activity_ttl.write().await = ttl_seconds.map(Duration::seconds);
+    }
+
+    pub fn register_controller(&self, ip: String, port: u16) -> bool {
+        self.register_controller_with_group(ip, port, None)
+    }
+
+    // Returns false (keeping the existing stats, but applying a new group if one is given) when
+    // ip:port is already registered, e.g. because a config lists a controller twice
+    pub fn register_controller_with_group(
+        &self,
+        ip: String,
+        port: u16,
+        group: Option<String>,
+    ) -> bool {
+        // Use composite key of IP:port to uniquely identify controllers
+        let key = format!("{}:{}", ip, port);
+        let entry = match self.controllers.entry(key) {
+            Entry::Occupied(mut existing) => {
+                if group.is_some() {
+                    existing.get_mut().group = group;
+                }
+                return false;
+            }
+            Entry::Vacant(entry) => entry,
+        };
+
         let status = ControllerStatus {
             ip: ip.clone(),
             port,
@@ -137,17 +276,41 @@ impl SenderMonitor {
             failure_count: 0,
             last_error: None,
             cooldown_until: None,
+            group,
+            registered_at: Utc::now(),
+            last_seen: None,
+            bytes_sent: 0,
+            throughput_bps: 0.0,
+            throughput_sample: None,
         };
-        // Use composite key of IP:port to uniquely identify controllers
+        entry.insert(status);
+        true
+    }
+
+    // Returns false if no controller is registered at ip:port
+    pub fn set_controller_group(&self, ip: &str, port: u16, group: Option<String>) -> bool {
         let key = format!("{}:{}", ip, port);
-        self.controllers.insert(key, status);
+        match self.controllers.get_mut(&key) {
+            Some(mut status) => {
+                status.group = group;
+                true
+            }
+            None => false,
+        }
     }
 
     pub async fn report_controller_success(&self, ip: &str, port: u16) {
+        self.report_controller_success_with_bytes(ip, port, 0).await;
+    }
+
+    // Success that also counts the bytes sent, for per-controller throughput
+    pub async fn report_controller_success_with_bytes(&self, ip: &str, port: u16, bytes: u64) {
         let key = format!("{}:{}", ip, port);
         if let Some(mut status) = self.controllers.get_mut(&key) {
             let now = Utc::now();
             status.last_success = Some(now);
+            status.last_seen = Some(now);
+            status.bytes_sent += bytes;
 
             // Check if we're still in cooldown period
             if let Some(cooldown_until) = status.cooldown_until {
@@ -173,6 +336,7 @@ impl SenderMonitor {
             status.is_routable = false;
             status.is_connecting = true; // Enter connecting state
             status.last_failure = Some(now);
+            status.last_seen = Some(now);
             status.failure_count += 1;
             status.last_error = Some(error.to_string());
 
@@ -184,6 +348,29 @@ impl SenderMonitor {
 
     pub fn report_frame(&self) {
         self.frame_counter.fetch_add(1, Ordering::Relaxed);
+
+        let now = Instant::now();
+        let mut frame_intervals = self.frame_intervals.lock().unwrap();
+        if let Some(last_frame) = frame_intervals.last_frame {
+            let interval_ms = (now - last_frame).as_secs_f64() * 1000.0;
+            if frame_intervals.intervals_ms.len() == FRAME_INTERVAL_HISTORY {
+                frame_intervals.intervals_ms.pop_front();
+            }
+            frame_intervals.intervals_ms.push_back(interval_ms);
+        }
+        frame_intervals.last_frame = Some(now);
+    }
+
+    // Restart the FPS average and frame interval history (e.g. when loading a new show);
+    // total_frames and uptime keep counting from monitor start
+    pub fn reset_frame_stats(&self) {
+        let total_frames = self.frame_counter.load(Ordering::Relaxed);
+        *self.fps_baseline.lock().unwrap() = (total_frames, Utc::now());
+        *self.frame_intervals.lock().unwrap() = FrameIntervals::default();
+    }
+
+    pub fn report_bytes(&self, bytes: u64) {
+        self.byte_counter.fetch_add(bytes, Ordering::Relaxed);
     }
 
     pub async fn update_system_stats(&self) {
@@ -191,25 +378,73 @@ impl SenderMonitor {
         let now = Utc::now();
         let uptime = (now - self.start_time).num_milliseconds() as f64 / 1000.0;
 
-        // Calculate FPS over the last second
-        let fps = if uptime > 0.0 {
-            total_frames as f64 / uptime
+        // Average FPS since start or the last reset_frame_stats
+        let (baseline_frames, baseline_time) = *self.fps_baseline.lock().unwrap();
+        let fps_window = (now - baseline_time).num_milliseconds() as f64 / 1000.0;
+        let fps = if fps_window > 0.0 {
+            total_frames.saturating_sub(baseline_frames) as f64 / fps_window
         } else {
             0.0
         };
 
+        // Bandwidth is measured over a window of at least a second so bursts average out
+        let total_bytes = self.byte_counter.load(Ordering::Relaxed);
+        let bytes_per_second = {
+            let mut sample = self.bandwidth_sample.write().await;
+            let elapsed = (now - sample.1).num_milliseconds() as f64 / 1000.0;
+            if elapsed >= 1.0 {
+                let rate = total_bytes.saturating_sub(sample.0) as f64 / elapsed;
+                *sample = (total_bytes, now);
+                Some(rate)
+            } else {
+                None
+            }
+        };
+
+        let (mut sorted_intervals, recent_intervals) = {
+            let frame_intervals = self.frame_intervals.lock().unwrap();
+            let intervals: Vec<f64> = frame_intervals.intervals_ms.iter().copied().collect();
+            let recent_start = intervals.len().saturating_sub(FRAME_INTERVAL_SPARKLINE);
+            let recent = intervals[recent_start..].to_vec();
+            (intervals, recent)
+        };
+        sorted_intervals.sort_by(|a, b| a.total_cmp(b));
+
         let mut stats = self.system_stats.write().await;
         stats.fps = fps;
+        stats.frame_interval_p50_ms = percentile(&sorted_intervals, 50.0);
+        stats.frame_interval_p95_ms = percentile(&sorted_intervals, 95.0);
+        stats.frame_interval_p99_ms = percentile(&sorted_intervals, 99.0);
+        stats.recent_frame_intervals_ms = recent_intervals;
         stats.uptime_seconds = uptime;
         stats.total_frames = total_frames;
+        stats.total_bytes = total_bytes;
+        if let Some(rate) = bytes_per_second {
+            stats.bytes_per_second = rate;
+        }
         stats.last_update = now;
     }
 
     pub async fn update_controller_statuses(&self) {
         // Check for controllers that have completed their cooldown period
         let now = Utc::now();
+        let activity_ttl = *self.activity_ttl.read().await;
 
         for mut status in self.controllers.iter_mut() {
+            status.update_throughput(now);
+
+            // Nothing reported within the TTL: don't claim it's working
+            if let Some(ttl) = activity_ttl {
+                let last_activity = status.last_seen.unwrap_or(status.registered_at);
+                if now - last_activity > ttl {
+                    status.is_routable = false;
+                    status.is_connecting = false;
+                    status.cooldown_until = None;
+                    status.last_error = Some(NO_RECENT_ACTIVITY.to_string());
+                    continue;
+                }
+            }
+
             if let Some(cooldown_until) = status.cooldown_until {
                 if now >= cooldown_until && status.is_connecting {
                     // Cooldown expired and no failures occurred during cooldown
@@ -232,8 +467,8 @@ impl SenderMonitor {
             .map(|entry| entry.value().clone())
             .collect();
 
-        // Sort controllers by IP address for consistent ordering
-        controllers.sort_by(|a, b| parse_ip_for_sorting(&a.ip).cmp(&parse_ip_for_sorting(&b.ip)));
+        // Sort controllers by IP address and port for consistent ordering
+        controllers.sort_by(compare_controllers);
 
         let system = self.system_stats.read().await.clone();
 
@@ -261,17 +496,42 @@ impl SenderMonitor {
             debug_state.is_paused = false;
             debug_state.current_debug_command = None;
             debug_state.debug_data = serde_json::json!({});
+            self.pause_tx
+                .send_if_modified(|paused| std::mem::replace(paused, false));
         }
+        self.publish_debug_state(&debug_state);
     }
 
     pub async fn set_debug_pause(&self, paused: bool) {
         let mut debug_state = self.debug_state.write().await;
         debug_state.is_paused = paused;
+        self.pause_tx
+            .send_if_modified(|current| std::mem::replace(current, paused) != paused);
+        self.publish_debug_state(&debug_state);
+    }
+
+    // Receiver that wakes whenever the pause state changes
+    pub fn subscribe_pause(&self) -> watch::Receiver<bool> {
+        self.pause_tx.subscribe()
+    }
+
+    // Receiver of a full DebugState snapshot after every debug mode/pause/command change
+    pub fn subscribe_debug_events(&self) -> broadcast::Receiver<DebugState> {
+        self.debug_events.subscribe()
+    }
+
+    // Called with the debug_state write lock held so subscribers see changes in order
+    fn publish_debug_state(&self, debug_state: &DebugState) {
+        // No subscribers is fine
+        let _ = self.debug_events.send(debug_state.clone());
     }
 
     pub async fn set_debug_command(&self, command: DebugCommand) {
         let mut debug_cmd = self.debug_command.write().await;
         *debug_cmd = Some(command.clone());
+        // Bumped while the command lock is held, so a reader that sees the new version always
+        // fetches the new command
+        self.debug_command_version.fetch_add(1, Ordering::Release);
 
         let mut debug_state = self.debug_state.write().await;
         debug_state.current_debug_command = Some(command.command_type.clone());
@@ -282,7 +542,9 @@ impl SenderMonitor {
                     debug_state.debug_data = serde_json::json!({
                         "orientation": mt.orientation.clone(),
                         "layer": mt.layer,
-                        "color": mt.color.clone()
+                        "all_layers": mt.all_layers,
+                        "color": mt.color.clone(),
+                        "target": mt.target.clone()
                     });
                 }
             }
@@ -300,6 +562,7 @@ impl SenderMonitor {
             }
             _ => {}
         }
+        self.publish_debug_state(&debug_state);
     }
 
     pub async fn get_debug_state(&self) -> DebugState {
@@ -310,6 +573,11 @@ impl SenderMonitor {
         self.debug_command.read().await.clone()
     }
 
+    // Cheap change check for callers polling get_debug_command every frame
+    pub fn get_debug_command_version(&self) -> u64 {
+        self.debug_command_version.load(Ordering::Acquire)
+    }
+
     pub async fn is_debug_mode(&self) -> bool {
         self.debug_state.read().await.is_debug_mode
     }
@@ -3,19 +3,28 @@ use crate::sender_monitor::{
 };
 use axum::{
     extract::{Json, State},
-    response::{Html, Json as JsonResponse},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, Json as JsonResponse,
+    },
     routing::{get, post},
     Router,
 };
+use futures_util::stream::{self, Stream};
 use runfiles::Runfiles;
 use serde_json::json;
+use std::convert::Infallible;
 use std::fs;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
 use tower_http::cors::CorsLayer;
 
 pub struct WebMonitor {
     sender_monitor: Arc<SenderMonitor>,
     bind_address: String,
+    started_at: Instant,
 }
 
 impl WebMonitor {
@@ -23,6 +32,7 @@ impl WebMonitor {
         Self {
             sender_monitor,
             bind_address: "0.0.0.0".to_string(),
+            started_at: Instant::now(),
         }
     }
 
@@ -32,12 +42,15 @@ impl WebMonitor {
     }
 
     pub fn create_router(&self) -> Router {
+        let started_at = self.started_at;
         Router::new()
             .route("/", get(dashboard_html))
+            .route("/health", get(move || health(started_at)))
             .route("/api/stats", get(get_stats))
             .route("/api/controllers", get(get_controllers))
             .route("/api/system", get(get_system_stats))
             .route("/api/debug/state", get(get_debug_state))
+            .route("/api/debug/events", get(debug_events))
             .route("/api/debug/world-dimensions", get(get_world_dimensions))
             .route("/api/debug/cubes", get(get_cubes))
             .route("/api/debug/mode", post(set_debug_mode))
@@ -52,10 +65,23 @@ impl WebMonitor {
         &self,
         port: u16,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let app = self.create_router();
+        let listener = self.bind(port).await?;
+        self.serve(listener).await
+    }
 
+    // Bind without serving yet, so the caller can learn the actual port (port 0 picks an
+    // ephemeral one) before handing the listener to serve()
+    pub async fn bind(&self, port: u16) -> std::io::Result<TcpListener> {
         let bind_addr = format!("{}:{}", self.bind_address, port);
-        let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+        TcpListener::bind(&bind_addr).await
+    }
+
+    pub async fn serve(
+        &self,
+        listener: TcpListener,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let app = self.create_router();
+        let port = listener.local_addr()?.port();
 
         // Show both localhost and the actual bind address for convenience
         if self.bind_address == "0.0.0.0" {
@@ -74,6 +100,11 @@ impl WebMonitor {
     }
 }
 
+// Liveness probe: cheap enough to poll often, unlike /api/stats
+async fn health(started_at: Instant) -> JsonResponse<serde_json::Value> {
+    JsonResponse(json!({ "ok": true, "uptime": started_at.elapsed().as_secs() }))
+}
+
 async fn dashboard_html() -> Html<String> {
     // Use runfiles to locate the HTML file
     let r = Runfiles::create().expect("Failed to create runfiles");
@@ -151,6 +182,37 @@ async fn get_debug_state(
     JsonResponse(json!(debug_state))
 }
 
+// Server-Sent Events stream of the debug state: the current state on connect, then a
+// "debug_state" event after every change
+async fn debug_events(
+    State(sender_monitor): State<Arc<SenderMonitor>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Subscribe before reading the current state so no change falls in between
+    let rx = sender_monitor.subscribe_debug_events();
+    let current = sender_monitor.get_debug_state().await;
+
+    let events = stream::unfold((Some(current), rx), |(pending, mut rx)| async move {
+        let state = match pending {
+            Some(state) => state,
+            None => loop {
+                match rx.recv().await {
+                    Ok(state) => break state,
+                    // Every event is a full snapshot, so a lagging client just skips ahead
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        };
+        let event = Event::default()
+            .event("debug_state")
+            .json_data(&state)
+            .unwrap_or_else(|_| Event::default().comment("failed to serialize debug state"));
+        Some((Ok(event), (None, rx)))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
 async fn get_world_dimensions(
     State(sender_monitor): State<Arc<SenderMonitor>>,
 ) -> JsonResponse<serde_json::Value> {
@@ -218,21 +280,87 @@ async fn set_mapping_tester(
         sender_monitor.set_debug_command(command).await;
         JsonResponse(json!({"success": true, "command": "clear"}))
     } else {
-        // Normal mapping tester command
+        // Normal mapping tester command; with "all": true the layer may be omitted
+        let all_layers = payload
+            .get("all")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let layer = payload
+            .get("layer")
+            .and_then(|v| v.as_u64())
+            .or(if all_layers { Some(0) } else { None });
         if let (Some(orientation), Some(layer), Some(color), Some(target)) = (
             payload.get("orientation").and_then(|v| v.as_str()),
-            payload.get("layer").and_then(|v| v.as_u64()),
+            layer,
             payload.get("color").and_then(|v| v.as_str()),
             payload.get("target").and_then(|v| v.as_str()),
         ) {
+            let mapping_tester = MappingTesterCommand {
+                orientation: orientation.to_string(),
+                layer: layer as usize,
+                color: color.to_string(),
+                target: target.to_string(),
+                all_layers,
+            };
+
+            // A cube target (cube_N, indexing the registered cube list) must name a registered
+            // cube when the list is known, and its layers are bounded by that cube's dimensions
+            // instead of the world's
+            let cubes = sender_monitor.get_cube_list().await;
+            let target_dimensions = if target == "world" {
+                sender_monitor.get_world_dimensions().await
+            } else if cubes.is_empty() {
+                None
+            } else {
+                match target
+                    .strip_prefix("cube_")
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(|index| cubes.get(index))
+                {
+                    Some(cube) => Some(cube.dimensions),
+                    None => {
+                        return JsonResponse(json!({
+                            "success": false,
+                            "error": format!(
+                                "Unknown target '{}' (valid: world, cube_0..cube_{})",
+                                target,
+                                cubes.len() - 1
+                            )
+                        }));
+                    }
+                }
+            };
+
+            // Validate the layer against the target's dimensions, if they are known
+            if let Some(dimensions) = target_dimensions {
+                match mapping_tester.layer_count(dimensions) {
+                    Some(count) if !all_layers && mapping_tester.layer >= count => {
+                        return JsonResponse(json!({
+                            "success": false,
+                            "error": format!(
+                                "Layer {} out of range for {} plane (valid: 0-{})",
+                                mapping_tester.layer,
+                                orientation,
+                                count.saturating_sub(1)
+                            )
+                        }));
+                    }
+                    Some(_) => {}
+                    None => {
+                        return JsonResponse(json!({
+                            "success": false,
+                            "error": format!(
+                                "Invalid orientation '{}': expected xy, xz, or yz",
+                                orientation
+                            )
+                        }));
+                    }
+                }
+            }
+
             let command = DebugCommand {
                 command_type: "mapping_tester".to_string(),
-                mapping_tester: Some(MappingTesterCommand {
-                    orientation: orientation.to_string(),
-                    layer: layer as usize,
-                    color: color.to_string(),
-                    target: target.to_string(),
-                }),
+                mapping_tester: Some(mapping_tester),
                 power_draw_tester: None,
             };
 
@@ -240,7 +368,7 @@ async fn set_mapping_tester(
             JsonResponse(json!({"success": true, "command": "mapping_tester"}))
         } else {
             JsonResponse(
-                json!({"success": false, "error": "Missing required fields: orientation, layer, color, target"}),
+                json!({"success": false, "error": "Missing required fields: orientation, layer (or all), color, target"}),
             )
         }
     }
@@ -0,0 +1,67 @@
+import json
+import logging
+import os
+import tempfile
+import time
+import unittest
+
+try:
+    from control_port_rust import ControlPortManager, init_logging
+
+    CONTROL_PORT_AVAILABLE = True
+except ImportError:
+    CONTROL_PORT_AVAILABLE = False
+
+
+class RecordingHandler(logging.Handler):
+    def __init__(self):
+        super().__init__()
+        self.records = []
+
+    def emit(self, record):
+        self.records.append(record)
+
+
+class TestControlPortLogging(unittest.TestCase):
+    """Test that Rust diagnostics reach Python's logging module."""
+
+    def setUp(self):
+        """Set up test fixtures."""
+        if not CONTROL_PORT_AVAILABLE:
+            self.skipTest("Rust control port implementation not available")
+
+    def test_connect_failure_is_logged(self):
+        """Test that a failed controller connection comes out as a logging record."""
+        handler = RecordingHandler()
+        logger = logging.getLogger("control_port_rs")
+        logger.addHandler(handler)
+        logger.setLevel(logging.DEBUG)
+        self.addCleanup(logger.removeHandler, handler)
+
+        with self.assertRaises(ValueError):
+            init_logging("loud")
+        init_logging("debug")
+
+        # Nothing listens on port 1, so the first connection attempt fails
+        config = {"controller_addresses": {"0": {"ip": "127.0.0.1", "port": 1}}}
+        with tempfile.TemporaryDirectory() as config_dir:
+            config_path = os.path.join(config_dir, "config.json")
+            with open(config_path, "w") as f:
+                json.dump(config, f)
+            manager = ControlPortManager(config_path)
+        manager.initialize()
+
+        deadline = time.monotonic() + 5
+        while time.monotonic() < deadline:
+            if any(r.levelno == logging.WARNING for r in handler.records):
+                break
+            time.sleep(0.05)
+
+        warnings = [r for r in handler.records if r.levelno == logging.WARNING]
+        self.assertTrue(warnings, "No warning was forwarded from the control port")
+        self.assertEqual(warnings[0].name, "control_port_rs.control_port")
+        self.assertIn("dip=0", warnings[0].getMessage())
+
+
+if __name__ == "__main__":
+    unittest.main(verbosity=2)
@@ -1,6 +1,7 @@
 import json
 import time
 import unittest
+import urllib.request
 
 try:
     from sender_monitor_rust import (
@@ -58,6 +59,19 @@ class TestSenderMonitorBasic(unittest.TestCase):
             f"Expected {len(test_controllers)} routable controllers, got {routable_count}",
         )
 
+    def test_duplicate_registration_keeps_stats(self):
+        """Test that registering a controller twice is reported and keeps its history."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+
+        self.assertTrue(monitor.register_controller("192.168.1.100", 51330))
+        monitor.report_controller_failure("192.168.1.100", 51330, "Connection timeout")
+        time.sleep(0.1)
+
+        self.assertFalse(monitor.register_controller("192.168.1.100", 51330))
+        self.assertEqual(monitor.get_controller_count(), 1)
+        self.assertEqual(monitor.get_routable_controller_count(), 0)
+
     def test_success_failure_reporting(self):
         """Test success and failure reporting."""
         monitor = create_sender_monitor()
@@ -88,6 +102,10 @@ class TestSenderMonitorBasic(unittest.TestCase):
         for _ in range(10):
             monitor.report_frame()
 
+        # Re-baselining FPS keeps accepting frames afterwards
+        monitor.reset_frame_stats()
+        monitor.report_frame()
+
     def test_cooldown_duration_setting(self):
         """Test cooldown duration setting."""
         monitor = create_sender_monitor()
@@ -96,6 +114,78 @@ class TestSenderMonitorBasic(unittest.TestCase):
         # Test cooldown duration setting
         monitor.set_cooldown_duration(5)  # 5 seconds
 
+    def test_wait_for_pause_change(self):
+        """Test that waiting on the pause state wakes up when it changes."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+
+        # Nothing changes, so this times out with the current state
+        self.assertFalse(monitor.wait_for_pause_change(False, 50))
+
+        monitor.set_debug_pause(True)
+        self.assertTrue(monitor.wait_for_pause_change(False, 1000))
+
+    def test_debug_command_version(self):
+        """Test that the debug command version counts the debug commands set."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+
+        self.assertEqual(monitor.get_debug_command_version(), 0)
+        self.assertIsNone(monitor.get_debug_command())
+
+        port = monitor.start_web_monitor_with_bind_address(0, "127.0.0.1")
+        monitor.set_world_dimensions(20, 20, 20)
+        time.sleep(0.1)
+
+        def set_mapping_tester(layer):
+            request = urllib.request.Request(
+                f"http://127.0.0.1:{port}/api/debug/mapping-tester",
+                data=json.dumps(
+                    {"orientation": "xy", "layer": layer, "color": "red", "target": "world"}
+                ).encode(),
+                headers={"Content-Type": "application/json"},
+            )
+            with urllib.request.urlopen(request, timeout=5) as response:
+                self.assertTrue(json.loads(response.read())["success"])
+
+        set_mapping_tester(0)
+        self.assertEqual(monitor.get_debug_command_version(), 1)
+        self.assertIsNotNone(monitor.get_debug_command())
+
+        set_mapping_tester(1)
+        self.assertEqual(monitor.get_debug_command_version(), 2)
+
+    def test_activity_ttl_setting(self):
+        """Test that the activity TTL expires controllers that stop reporting."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+        port = monitor.start_web_monitor_with_bind_address(0, "127.0.0.1")
+
+        monitor.register_controller("192.168.1.100", 51330)
+        monitor.register_controller("192.168.1.101", 51331)
+        monitor.set_activity_ttl(1)
+        time.sleep(0.1)
+
+        def get_controllers():
+            url = f"http://127.0.0.1:{port}/api/controllers"
+            with urllib.request.urlopen(url, timeout=5) as response:
+                body = json.loads(response.read())
+            return body, {c["port"]: c for c in body["controllers"]}
+
+        # Freshly registered controllers count as active
+        body, _ = get_controllers()
+        self.assertEqual(body["routable"], 2)
+
+        time.sleep(1.2)
+        monitor.report_controller_success("192.168.1.100", 51330)
+        time.sleep(0.1)
+
+        body, controllers = get_controllers()
+        self.assertEqual(body["routable"], 1)
+        self.assertTrue(controllers[51330]["is_routable"])
+        self.assertFalse(controllers[51331]["is_routable"])
+        self.assertEqual(controllers[51331]["last_error"], "No recent activity")
+
     def test_same_ip_different_ports(self):
         """Test that controllers with same IP but different ports are treated separately."""
         monitor = create_sender_monitor()
@@ -151,6 +241,58 @@ class TestSenderMonitorWebInterface(unittest.TestCase):
         monitor = create_sender_monitor_with_web_interface(port=8083, cooldown_seconds=10)
         self.assertIsNotNone(monitor, "Failed to create sender monitor with web interface")
 
+    def test_web_interface_ephemeral_port(self):
+        """Test that binding to port 0 reports the port the OS assigned."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+        self.assertIsNone(monitor.get_web_monitor_port())
+
+        port = monitor.start_web_monitor_with_bind_address(0, "127.0.0.1")
+        self.assertGreater(port, 0)
+        self.assertEqual(monitor.get_web_monitor_port(), port)
+
+    def test_health_endpoint(self):
+        """Test that /health answers with a small liveness body."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+        port = monitor.start_web_monitor_with_bind_address(0, "127.0.0.1")
+
+        with urllib.request.urlopen(f"http://127.0.0.1:{port}/health", timeout=5) as response:
+            self.assertEqual(response.status, 200)
+            body = json.loads(response.read())
+        self.assertTrue(body["ok"])
+        self.assertGreaterEqual(body["uptime"], 0)
+
+    def test_mapping_tester_cube_target(self):
+        """Test that cube_N mapping tester targets index the registered cube list."""
+        monitor = create_sender_monitor()
+        self.assertIsNotNone(monitor)
+        port = monitor.start_web_monitor_with_bind_address(0, "127.0.0.1")
+        monitor.set_cube_list(
+            [("Cube 1", (0, 0, 0), (20, 20, 20)), ("Cube 2", (20, 0, 0), (20, 20, 10))]
+        )
+        time.sleep(0.1)
+
+        def post(target, layer):
+            request = urllib.request.Request(
+                f"http://127.0.0.1:{port}/api/debug/mapping-tester",
+                data=json.dumps(
+                    {"orientation": "xy", "layer": layer, "color": "red", "target": target}
+                ).encode(),
+                headers={"Content-Type": "application/json"},
+            )
+            with urllib.request.urlopen(request, timeout=5) as response:
+                return json.loads(response.read())
+
+        self.assertTrue(post("cube_1", 9)["success"])
+        self.assertEqual(monitor.get_debug_command()["mapping_tester"]["target"], "cube_1")
+        # Bounded by that cube's own length
+        self.assertFalse(post("cube_1", 10)["success"])
+
+        rejected = post("cube_2", 0)
+        self.assertFalse(rejected["success"])
+        self.assertIn("cube_0..cube_1", rejected["error"])
+
     def test_web_interface_with_controllers(self):
         """Test web interface with registered controllers."""
         monitor = create_sender_monitor_with_web_interface(port=8084, cooldown_seconds=10)